@@ -0,0 +1,255 @@
+// Copyright (c) 2022-2025 Objectionary.com
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included
+// in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Alerts, which check the graph on demand and on every [`Sodg::put`],
+//! see [`Sodg::alert_on`].
+
+use crate::{Alert, Label, Sodg, BRANCH_NONE};
+use anyhow::{anyhow, Result};
+
+impl<const N: usize> Sodg<N> {
+    /// Attach a new alert to the graph.
+    ///
+    /// For example, this alert complains about vertices that have
+    /// no edges:
+    ///
+    /// ```
+    /// use sodg::Sodg;
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.alert_on(|g, vx| {
+    ///     vx.into_iter()
+    ///         .filter(|v| g.kids(*v).count() == 0)
+    ///         .map(|v| format!("ν{v} has no edges"))
+    ///         .collect()
+    /// });
+    /// g.add(0);
+    /// assert!(g.validate(&[0]).is_err());
+    /// ```
+    ///
+    /// An alert gets the graph and the vertices to check, and returns
+    /// a list of problems found, which is empty if everything is fine.
+    /// Alerts are not saved by [`Sodg::save`].
+    ///
+    /// Alerts also check every vertex that gets data, see [`Sodg::try_put`].
+    pub fn alert_on(&mut self, a: Alert<N>) {
+        self.alerts.push(a);
+    }
+
+    /// Remove all alerts attached to the graph.
+    pub fn alerts_off(&mut self) {
+        self.alerts.clear();
+    }
+
+    /// Run all alerts over the vertices provided and fail on the first
+    /// alert that finds any problems.
+    ///
+    /// # Errors
+    ///
+    /// If any alert finds problems, an error will be returned with
+    /// all messages of this alert.
+    pub fn validate(&self, vx: &[usize]) -> Result<()> {
+        for a in &self.alerts {
+            let msgs = a(self, vx.to_vec());
+            if !msgs.is_empty() {
+                return Err(anyhow!("{}", msgs.join("; ")));
+            }
+        }
+        Ok(())
+    }
+
+    /// Run all alerts over all vertices of the graph and
+    /// return all problems found.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Sodg;
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.alert_on(|_, vx| vx.into_iter().map(|v| format!("ν{v} is bad")).collect());
+    /// g.alert_on(|_, vx| vx.into_iter().map(|v| format!("ν{v} is ugly")).collect());
+    /// g.add(0);
+    /// assert_eq!(vec!["ν0 is bad", "ν0 is ugly"], g.validate_all());
+    /// ```
+    ///
+    /// Unlike [`Sodg::validate`], this doesn't stop on the first alert
+    /// that finds anything, but runs all of them.
+    #[must_use]
+    pub fn validate_all(&self) -> Vec<String> {
+        let vx: Vec<usize> = self
+            .vertices
+            .iter()
+            .filter(|(_, vtx)| vtx.branch != BRANCH_NONE)
+            .map(|(v, _)| v)
+            .collect();
+        self.alerts
+            .iter()
+            .flat_map(|a| a(self, vx.clone()))
+            .collect()
+    }
+}
+
+/// Make an alert that complains about vertices with two or more edges
+/// under the same label.
+///
+/// For example:
+///
+/// ```
+/// use sodg::{alerts, Label, Sodg};
+/// let mut g : Sodg<16> = Sodg::empty(256);
+/// g.alert_on(alerts::single_edge_per_label());
+/// g.add(0);
+/// g.add(1);
+/// g.bind(0, 1, Label::Alpha(0));
+/// assert!(g.validate_all().is_empty());
+/// ```
+///
+/// Edges are compared by what their labels mean, not by how they
+/// are stored. Thus, `Label::Alpha(0)` and `Label::Str` holding `"α0"` are
+/// two different keys for the vertex, but the same label for a reader.
+/// Such duplicates may appear after a manual edit or a careless merge,
+/// while [`Sodg::dedup_edges`] removes those of them leading to
+/// the same vertex.
+#[must_use]
+pub fn single_edge_per_label<const N: usize>() -> Alert<N> {
+    |g, vx| {
+        let mut msgs = vec![];
+        for v in vx {
+            let mut seen: Vec<Label> = vec![];
+            for (a, _) in g.kids(v) {
+                let name = a.canonical();
+                if seen.contains(&name) {
+                    msgs.push(format!("ν{v} has more than one edge labeled as '{name}'"));
+                } else {
+                    seen.push(name);
+                }
+            }
+        }
+        msgs
+    }
+}
+
+/// Make an alert that complains about vertices with data that is
+/// an `f64` NaN.
+///
+/// For example:
+///
+/// ```
+/// use sodg::{alerts, Hex, Sodg};
+/// let mut g : Sodg<16> = Sodg::empty(256);
+/// g.alert_on(alerts::no_nan_floats());
+/// g.add(0);
+/// assert!(g.try_put(0, &Hex::from(f64::NAN)).is_err());
+/// assert!(g.try_put(0, &Hex::from(2.5)).is_ok());
+/// ```
+///
+/// With this alert, [`Sodg::put`] of a NaN panics.
+///
+/// Any data of eight bytes is read as an `f64`, since there is no way to
+/// tell a float from other data. Thus, don't use this alert if some
+/// vertices keep eight-byte data that is not a float.
+#[must_use]
+pub fn no_nan_floats<const N: usize>() -> Alert<N> {
+    |g, vx| {
+        vx.into_iter()
+            .filter(|v| {
+                g.data_ref(*v)
+                    .filter(|d| d.len() == 8)
+                    .and_then(|d| d.to_f64().ok())
+                    .is_some_and(f64::is_nan)
+            })
+            .map(|v| format!("ν{v} contains NaN"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+use crate::Hex;
+
+#[test]
+fn reports_all_problems() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.alert_on(|g, vx| {
+        vx.into_iter()
+            .filter(|v| g.kids(*v).count() == 0)
+            .map(|v| format!("ν{v} is a leaf"))
+            .collect()
+    });
+    g.alert_on(|g, vx| {
+        vx.into_iter()
+            .filter(|v| *v > 0 && g.kid(*v, Label::Greek('ρ')).is_none())
+            .map(|v| format!("ν{v} has no parent"))
+            .collect()
+    });
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    let all = g.validate_all();
+    assert_eq!(vec!["ν1 is a leaf", "ν1 has no parent"], all);
+    let first = g.validate(&[0, 1]).unwrap_err().to_string();
+    assert_eq!("ν1 is a leaf", first);
+    g.alerts_off();
+    assert!(g.validate_all().is_empty());
+}
+
+#[test]
+fn finds_duplicate_labels() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.alert_on(single_edge_per_label());
+    g.add(0);
+    g.add(1);
+    g.add(2);
+    g.bind(0, 1, Label::Alpha(0));
+    g.bind(0, 2, Label::Greek('ρ'));
+    assert!(g.validate(&[0, 1, 2]).is_ok());
+    g.bind(0, 2, Label::Str(['α', '0', ' ', ' ', ' ', ' ', ' ', ' ']));
+    assert_eq!(
+        vec!["ν0 has more than one edge labeled as 'α0'"],
+        g.validate_all()
+    );
+}
+
+#[test]
+fn rejects_nan_floats() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.alert_on(no_nan_floats());
+    g.add(0);
+    g.put(0, &Hex::from(2.5));
+    g.add(1);
+    g.put(1, &Hex::from_str_bytes("hello"));
+    g.add(2);
+    assert!(g.validate(&[0, 1, 2]).is_ok());
+    let err = g.try_put(2, &Hex::from(f64::NAN)).unwrap_err();
+    assert!(format!("{err:#}").contains("ν2 contains NaN"), "{err:#}");
+    assert!(g.data_ref(2).is_none());
+    assert!(g.validate(&[0, 1, 2]).is_ok());
+    assert!(g.check_invariants().is_ok());
+    let err = g.try_put(0, &Hex::from(f64::NAN)).unwrap_err();
+    assert!(format!("{err:#}").contains("ν0 contains NaN"), "{err:#}");
+    assert_eq!(Hex::from(2.5), g.data(0).unwrap());
+}
+
+#[test]
+#[should_panic(expected = "ν0 contains NaN")]
+fn panics_on_nan_put() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.alert_on(no_nan_floats());
+    g.add(0);
+    g.put(0, &Hex::from(f64::NAN));
+}
@@ -0,0 +1,168 @@
+// Copyright (c) 2022-2025 Objectionary.com
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included
+// in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{Sodg, BRANCH_NONE};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+impl<const N: usize> Sodg<N> {
+    /// Find the shortest path from `from` to `to`, by the number of edges,
+    /// and return all vertices on it, including both ends.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.add(2);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// g.bind(1, 2, Label::Alpha(0));
+    /// g.bind(0, 2, Label::Alpha(1));
+    /// assert_eq!(vec![0, 2], g.shortest_path(0, 2).unwrap());
+    /// assert!(g.shortest_path(2, 0).is_none());
+    /// ```
+    ///
+    /// The edges are followed only in their direction. If there are
+    /// many shortest paths, the one going through vertices with smaller IDs
+    /// is returned. If any of the vertices is absent, `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// If any of the vertices is out of the capacity of the graph, it will panic.
+    #[must_use]
+    pub fn shortest_path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        if self.vertices.get(from).unwrap().branch == BRANCH_NONE
+            || self.vertices.get(to).unwrap().branch == BRANCH_NONE
+        {
+            return None;
+        }
+        let mut prev: HashMap<usize, usize> = HashMap::new();
+        let mut todo = VecDeque::new();
+        prev.insert(from, from);
+        todo.push_back(from);
+        while let Some(v) = todo.pop_front() {
+            if v == to {
+                let mut path = vec![to];
+                let mut cur = to;
+                while cur != from {
+                    cur = prev[&cur];
+                    path.push(cur);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            let mut next: Vec<usize> = self.kids(v).map(|(_, k)| *k).collect();
+            next.sort_unstable();
+            for k in next {
+                if prev.contains_key(&k) || self.vertices.get(k).unwrap().branch == BRANCH_NONE {
+                    continue;
+                }
+                prev.insert(k, v);
+                todo.push_back(k);
+            }
+        }
+        None
+    }
+
+    /// Count live vertices by the number of edges going out of them.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// let h = g.degree_histogram();
+    /// assert_eq!(Some(&1), h.get(&1));
+    /// assert_eq!(Some(&1), h.get(&0));
+    /// ```
+    ///
+    /// The keys are the out-degrees, the values are the numbers of vertices
+    /// with such a degree. The largest key is the smallest `N` that would
+    /// fit this graph.
+    #[must_use]
+    pub fn degree_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut h = BTreeMap::new();
+        for v in self {
+            *h.entry(self.kids(v).count()).or_insert(0) += 1;
+        }
+        h
+    }
+}
+
+#[cfg(test)]
+use crate::Label;
+
+#[test]
+fn finds_direct_path() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    assert_eq!(vec![0, 1], g.shortest_path(0, 1).unwrap());
+}
+
+#[test]
+fn finds_multi_hop_path() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    for v in 0..6 {
+        g.add(v);
+    }
+    g.bind(0, 3, Label::Alpha(0));
+    g.bind(0, 1, Label::Alpha(1));
+    g.bind(1, 2, Label::Alpha(0));
+    g.bind(3, 2, Label::Alpha(0));
+    g.bind(2, 4, Label::Alpha(0));
+    g.bind(4, 5, Label::Alpha(0));
+    assert_eq!(vec![0, 1, 2, 4, 5], g.shortest_path(0, 5).unwrap());
+}
+
+#[test]
+fn finds_no_path() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    assert!(g.shortest_path(1, 0).is_none());
+    assert!(g.shortest_path(0, 7).is_none());
+}
+
+#[test]
+fn finds_path_to_itself() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(3);
+    assert_eq!(vec![3], g.shortest_path(3, 3).unwrap());
+}
+
+#[test]
+fn counts_degrees() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    for v in 0..3 {
+        g.add(v);
+    }
+    g.bind(0, 1, Label::Alpha(0));
+    g.bind(0, 2, Label::Alpha(1));
+    let h = g.degree_histogram();
+    assert_eq!(BTreeMap::from([(0, 2), (2, 1)]), h);
+    assert!(Sodg::<16>::empty(256).degree_histogram().is_empty());
+}
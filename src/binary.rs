@@ -0,0 +1,344 @@
+// Copyright (c) 2022-2025 Objectionary.com
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included
+// in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{Hex, Label, Persistence, SharedHex, Sodg, BRANCH_NONE, BRANCH_STATIC};
+use anyhow::{anyhow, Context, Result};
+use log::trace;
+
+/// The first bytes of a buffer made by [`Sodg::to_bytes`].
+const MAGIC: &[u8; 4] = b"SODG";
+
+/// The version of the format of [`Sodg::to_bytes`].
+const VERSION: u8 = 1;
+
+impl<const N: usize> Sodg<N> {
+    /// Print the entire [`Sodg`] into a compact binary format, which is
+    /// easy to read in other languages.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// g.put(1, &Hex::from(42));
+    /// let bytes = g.to_bytes();
+    /// let mut after : Sodg<16> = Sodg::from_bytes(&bytes).unwrap();
+    /// assert_eq!(42, after.data(1).unwrap().to_i64().unwrap());
+    /// ```
+    ///
+    /// Unlike [`Sodg::save`], the format doesn't depend on serde or
+    /// bincode and won't change without changing its version. All numbers
+    /// are little-endian. The buffer starts with a header:
+    ///
+    /// ```text
+    /// magic       4 bytes   "SODG"
+    /// version     u8        1
+    /// capacity    u64       the capacity of the graph
+    /// vertices    u64       the number of vertex records that follow
+    /// ```
+    ///
+    /// Then, each vertex, in ascending order of IDs:
+    ///
+    /// ```text
+    /// id          u64
+    /// branch      u32
+    /// persistence u8        0 - empty, 1 - stored, 2 - taken
+    /// data        u64 + N   the length of data and its bytes
+    /// edges       u32       the number of edges that follow
+    /// ```
+    ///
+    /// Each edge is a label followed by the ID of the vertex it leads to,
+    /// as `u64`. The label starts with a tag: `0` is followed by a `u32`
+    /// Unicode scalar of [`Label::Greek`], `1` is followed by a `u64` index
+    /// of [`Label::Alpha`], `2` is followed by eight `u32` Unicode
+    /// scalars of [`Label::Str`], and `3` is followed by a `u32` index
+    /// of [`Label::Id`]. Absent vertices are not printed.
+    ///
+    /// The buffer ends with the symbol table of [`Sodg::intern`]:
+    ///
+    /// ```text
+    /// symbols     u64       the number of names that follow
+    /// name        u64 + N   the length of a UTF-8 name and its bytes
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// May panic if vertices are absent in the graph (should never happen, though).
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = vec![];
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        let alive: Vec<usize> = self.keys();
+        out.extend_from_slice(&(self.vertices.capacity() as u64).to_le_bytes());
+        out.extend_from_slice(&(alive.len() as u64).to_le_bytes());
+        for v in alive {
+            let vtx = self.vertices.get(v).unwrap();
+            out.extend_from_slice(&(v as u64).to_le_bytes());
+            out.extend_from_slice(&(vtx.branch as u32).to_le_bytes());
+            out.push(match vtx.persistence {
+                Persistence::Empty => 0,
+                Persistence::Stored => 1,
+                Persistence::Taken => 2,
+            });
+            let data = if vtx.persistence == Persistence::Empty {
+                &[][..]
+            } else {
+                vtx.data.bytes()
+            };
+            out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            out.extend_from_slice(data);
+            out.extend_from_slice(&(vtx.edges.len() as u32).to_le_bytes());
+            for (a, to) in &vtx.edges {
+                match a {
+                    Label::Greek(c) => {
+                        out.push(0);
+                        out.extend_from_slice(&(*c as u32).to_le_bytes());
+                    }
+                    Label::Alpha(i) => {
+                        out.push(1);
+                        out.extend_from_slice(&(*i as u64).to_le_bytes());
+                    }
+                    Label::Str(cs) => {
+                        out.push(2);
+                        for c in cs {
+                            out.extend_from_slice(&(*c as u32).to_le_bytes());
+                        }
+                    }
+                    Label::Id(i) => {
+                        out.push(3);
+                        out.extend_from_slice(&i.to_le_bytes());
+                    }
+                }
+                out.extend_from_slice(&(*to as u64).to_le_bytes());
+            }
+        }
+        out.extend_from_slice(&(self.symbols.len() as u64).to_le_bytes());
+        for name in &self.symbols.names {
+            out.extend_from_slice(&(name.len() as u64).to_le_bytes());
+            out.extend_from_slice(name.as_bytes());
+        }
+        trace!(
+            "#to_bytes: printed {} vertices into {} bytes",
+            self.len(),
+            out.len()
+        );
+        out
+    }
+
+    /// Make a new [`Sodg`] from the bytes previously made by [`Sodg::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// If the buffer is truncated or broken, or if it has a vertex with
+    /// more than `N` edges, an error will be returned.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut r = Reader { bytes, pos: 0 };
+        if r.take(MAGIC.len())? != MAGIC {
+            return Err(anyhow!("This is not a SODG, the magic prefix is wrong"));
+        }
+        let version = r.u8()?;
+        if version != VERSION {
+            return Err(anyhow!(
+                "Version {version} is not supported, only {VERSION} is"
+            ));
+        }
+        let cap = r.usize()?;
+        let total = r.usize()?;
+        let mut g = Self::empty(cap);
+        for _ in 0..total {
+            let v = r.usize()?;
+            if v >= cap {
+                return Err(anyhow!("The vertex ν{v} is out of capacity {cap}"));
+            }
+            g.read_vertex(v, &mut r)
+                .with_context(|| format!("Can't read ν{v}"))?;
+        }
+        let names = r.usize()?;
+        for _ in 0..names {
+            let len = r.usize()?;
+            let name = std::str::from_utf8(r.take(len)?).context("Can't read a symbol")?;
+            g.symbols.intern(name);
+        }
+        if r.pos != bytes.len() {
+            return Err(anyhow!(
+                "There are {} extra bytes after {total} vertices",
+                bytes.len() - r.pos
+            ));
+        }
+        trace!(
+            "#from_bytes: read {} vertices from {} bytes",
+            g.len(),
+            bytes.len()
+        );
+        Ok(g)
+    }
+
+    /// Read one vertex record, except its ID, into vertex `v`.
+    fn read_vertex(&mut self, v: usize, r: &mut Reader) -> Result<()> {
+        let branch = r.u32()? as usize;
+        if branch == BRANCH_NONE || branch > self.vertices.capacity() + BRANCH_STATIC {
+            return Err(anyhow!("The branch no.{branch} is out of range"));
+        }
+        if branch >= self.branches.capacity() {
+            self.grow_branches((branch + 1).max(self.branches.capacity() * 2));
+        }
+        let persistence = match r.u8()? {
+            0 => Persistence::Empty,
+            1 => Persistence::Stored,
+            2 => Persistence::Taken,
+            p => return Err(anyhow!("The persistence {p} is unknown")),
+        };
+        let len = r.usize()?;
+        let data = Hex::from_slice(r.take(len)?);
+        let total = r.u32()? as usize;
+        if total > N {
+            return Err(anyhow!(
+                "The vertex has {total} edges, but this Sodg allows only {N}"
+            ));
+        }
+        let vtx = self.vertices.get_mut(v).unwrap();
+        for _ in 0..total {
+            let a = match r.u8()? {
+                0 => Label::Greek(r.char()?),
+                1 => Label::Alpha(r.usize()?),
+                2 => {
+                    let mut cs = [' '; 8];
+                    for c in &mut cs {
+                        *c = r.char()?;
+                    }
+                    Label::Str(cs)
+                }
+                3 => Label::Id(r.u32()?),
+                t => return Err(anyhow!("The label tag {t} is unknown")),
+            };
+            vtx.edges.insert(a, r.usize()?);
+        }
+        if vtx.branch == BRANCH_NONE && branch != BRANCH_NONE {
+            self.alive += 1;
+        }
+        vtx.branch = branch;
+        vtx.data = SharedHex::new(data);
+        if persistence == Persistence::Stored {
+            *self.stores.get_mut(branch).unwrap() += 1;
+        }
+        vtx.persistence = persistence;
+        if branch > BRANCH_STATIC {
+            self.branches
+                .get_mut(branch)
+                .unwrap()
+                .try_push(v)
+                .map_err(|e| anyhow!("Can't put it into branch no.{branch}: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// A cursor over the bytes made by [`Sodg::to_bytes`].
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Take the next `n` bytes.
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|e| *e <= self.bytes.len())
+            .with_context(|| {
+                format!(
+                    "The buffer is truncated, {n} bytes expected at position {}, while it is {} bytes long",
+                    self.pos,
+                    self.bytes.len()
+                )
+            })?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    /// Read one byte.
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Read a little-endian `u32`.
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    /// Read a little-endian `u64`, as `usize`.
+    fn usize(&mut self) -> Result<usize> {
+        let n = u64::from_le_bytes(self.take(8)?.try_into()?);
+        Ok(usize::try_from(n)?)
+    }
+
+    /// Read a Unicode scalar, as a little-endian `u32`.
+    fn char(&mut self) -> Result<char> {
+        let n = self.u32()?;
+        char::from_u32(n).with_context(|| format!("{n} is not a valid char"))
+    }
+}
+
+#[cfg(test)]
+use std::str::FromStr;
+
+#[test]
+fn prints_and_reads_bytes() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("foo").unwrap());
+    g.bind(0, 1, Label::from_str("ρ").unwrap());
+    g.add(2);
+    g.bind(1, 2, Label::Alpha(7));
+    g.put(2, &Hex::from_str_bytes("Hello, world!"));
+    g.add(3);
+    g.bind(0, 3, Label::Alpha(0));
+    g.put(3, &Hex::from(42));
+    g.data(3);
+    let long = g.intern("a-very-long-name");
+    g.bind(3, 1, long);
+    let after: Sodg<16> = Sodg::from_bytes(&g.to_bytes()).unwrap();
+    assert_eq!("a-very-long-name", after.label_name(long).unwrap());
+    assert_eq!(g.to_xml_full().unwrap(), after.to_xml_full().unwrap());
+    assert!(after.check_invariants().is_ok());
+}
+
+#[test]
+fn refuses_truncated_bytes() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.put(1, &Hex::from(42));
+    let bytes = g.to_bytes();
+    for len in 0..bytes.len() {
+        assert!(Sodg::<16>::from_bytes(&bytes[..len]).is_err());
+    }
+    let msg = format!("{:#}", Sodg::<16>::from_bytes(&bytes[..30]).unwrap_err());
+    assert!(msg.contains("truncated"), "{msg}");
+}
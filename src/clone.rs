@@ -18,7 +18,9 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::Sodg;
+use crate::{Persistence, Sodg};
+use itertools::Itertools;
+use std::collections::HashMap;
 
 impl<const N: usize> Clone for Sodg<N> {
     /// Make a clone of the graph.
@@ -28,12 +30,77 @@ impl<const N: usize> Clone for Sodg<N> {
             branches: self.branches.clone(),
             stores: self.stores.clone(),
             next_v: self.next_v,
+            alive: self.alive,
+            listener: self.listener,
+            finds: self.finds.clone(),
+            symbols: self.symbols.clone(),
+            alerts: self.alerts.clone(),
+            lambdas: self.lambdas.clone(),
+            strict: self.strict,
+            auto_collect: self.auto_collect,
+            mutations: self.mutations,
+            sweep: self.sweep.clone(),
         }
     }
 }
 
+impl<const N: usize> Sodg<N> {
+    /// Make a clone of the graph with as small capacity as possible,
+    /// renumbering the vertices densely.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(42);
+    /// g.bind(0, 42, Label::Alpha(0));
+    /// let c = g.clone_compact();
+    /// assert_eq!(2, c.len());
+    /// assert_eq!(Some(1), c.kid(0, Label::Alpha(0)));
+    /// ```
+    ///
+    /// The vertices keep their order: the smallest ID becomes `ν0`,
+    /// the next one becomes `ν1`, and so on. Their data is copied together
+    /// with its persistence state, and so is the table of symbols made
+    /// by [`Sodg::intern`]. Listeners, lambdas, alerts, and other
+    /// settings of the graph are not copied; use [`Clone`] if you need
+    /// a faithful copy.
+    ///
+    /// # Panics
+    ///
+    /// May panic if the graph is broken (should never happen, though).
+    #[must_use]
+    pub fn clone_compact(&self) -> Self {
+        let mut keys = self.keys();
+        keys.sort_unstable();
+        let ids: HashMap<usize, usize> = keys.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        let mut ng = Self::empty(keys.len().max(1));
+        ng.symbols.clone_from(&self.symbols);
+        for v in 0..keys.len() {
+            ng.add(v);
+        }
+        for v1 in &keys {
+            let vtx = self.vertices.get(*v1).unwrap();
+            for (a, v2) in vtx.edges.iter().sorted_by_key(|e| e.0) {
+                ng.bind(ids[v1], ids[v2], *a);
+            }
+        }
+        for v in &keys {
+            let vtx = self.vertices.get(*v).unwrap();
+            match vtx.persistence {
+                Persistence::Stored => ng.put(ids[v], &vtx.data),
+                Persistence::Taken => ng.put_taken(ids[v], &vtx.data),
+                Persistence::Empty => {}
+            }
+        }
+        ng
+    }
+}
+
 #[cfg(test)]
-use crate::Label;
+use crate::{Hex, Label};
 
 #[test]
 fn makes_a_clone() {
@@ -46,8 +113,48 @@ fn makes_a_clone() {
 }
 
 #[test]
+#[allow(clippy::redundant_clone)]
 fn makes_an_empty_clone() {
     let g: Sodg<16> = Sodg::empty(256);
     let c = g.clone();
     assert_eq!(0, c.len());
 }
+
+#[test]
+fn makes_a_compact_clone() {
+    let mut g: Sodg<16> = Sodg::empty(1024);
+    g.add(0);
+    g.add(500);
+    g.add(1000);
+    g.bind(0, 500, Label::Alpha(0));
+    g.bind(500, 1000, Label::Alpha(1));
+    g.bind(1000, 0, Label::Greek('ρ'));
+    g.put(500, &Hex::from(42));
+    g.put(1000, &Hex::from(7));
+    g.data(1000);
+    let c = g.clone_compact();
+    assert_eq!(3, c.vertices.capacity());
+    assert!(c.vertices.capacity() < g.vertices.capacity());
+    assert_eq!(vec![0, 1, 2], c.keys());
+    assert_eq!(Some(1), c.kid(0, Label::Alpha(0)));
+    assert_eq!(Some(2), c.kid(1, Label::Alpha(1)));
+    assert_eq!(Some(0), c.kid(2, Label::Greek('ρ')));
+    assert_eq!(Hex::from(42), c.peek(1).unwrap());
+    assert_eq!(Hex::from(7), c.peek(2).unwrap());
+    for (v, c_v) in [(0, 0), (500, 1), (1000, 2)] {
+        assert_eq!(g.kids(v).count(), c.kids(c_v).count());
+    }
+}
+
+#[test]
+fn keeps_symbols_in_compact_clone() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(100);
+    let a = g.intern("a-very-long-name");
+    g.bind(0, 100, a);
+    let c = g.clone_compact();
+    let b = c.kids(0).next().unwrap().0;
+    assert_eq!("a-very-long-name", c.label_name(*b).unwrap());
+    assert_eq!(Some(a), c.symbol("a-very-long-name"));
+}
@@ -18,30 +18,80 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::{Hex, Persistence, Sodg, Vertex, MAX_BRANCHES};
+use crate::{
+    Label, LabelTable, Persistence, Script, SharedHex, Sodg, Vertex, BRANCH_STATIC, MAX_BRANCHES,
+};
+use anyhow::{Context, Result};
 use emap::Map;
+use std::collections::HashMap;
 
 impl<const N: usize> Sodg<N> {
     /// Make an empty [`Sodg`], with no vertices and no edges.
     ///
+    /// There is room for 16 branches, which grows when all of them
+    /// are busy, see [`Sodg::empty_with`].
+    ///
     /// # Panics
     ///
     /// May panic if vertices provided to alerts are absent (should never happen, though).
     #[must_use]
     pub fn empty(cap: usize) -> Self {
+        Self::empty_with(cap, MAX_BRANCHES)
+    }
+
+    /// Make an empty [`Sodg`], with no vertices and no edges,
+    /// and with room for the given number of branches.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty_with(256, 1024);
+    /// for v in 0..100 {
+    ///     g.add(v * 2);
+    ///     g.add(v * 2 + 1);
+    ///     g.bind(v * 2, v * 2 + 1, Label::Alpha(0));
+    ///     g.put(v * 2 + 1, &Hex::from(42));
+    /// }
+    /// assert_eq!(200, g.len());
+    /// ```
+    ///
+    /// Every pair of vertices bound together, which are not bound to any
+    /// other vertices, make a new branch. When all branches are busy, the room
+    /// for them grows twice, which costs a copy of all of them. Thus, if you
+    /// expect many independent pieces of data in the graph, it's better to make
+    /// room for them in advance. There are always at least two branches:
+    /// the one for absent vertices and the static one.
+    ///
+    /// # Panics
+    ///
+    /// May panic if vertices provided to alerts are absent (should never happen, though).
+    #[must_use]
+    pub fn empty_with(cap: usize, branches: usize) -> Self {
+        let branches = branches.max(BRANCH_STATIC + 1);
         let mut g = Self {
             vertices: Map::with_capacity_some(
                 cap,
                 Vertex {
                     branch: 0,
-                    data: Hex::empty(),
+                    data: SharedHex::default(),
                     persistence: Persistence::Empty,
                     edges: micromap::Map::new(),
                 },
             ),
-            stores: Map::with_capacity_some(MAX_BRANCHES, 0),
-            branches: Map::with_capacity_some(MAX_BRANCHES, microstack::Stack::new()),
+            stores: Map::with_capacity_some(branches, 0),
+            branches: Map::with_capacity_some(branches, microstack::Stack::new()),
             next_v: 0,
+            alive: 0,
+            listener: None,
+            finds: None,
+            symbols: LabelTable::default(),
+            alerts: vec![],
+            lambdas: HashMap::new(),
+            strict: false,
+            auto_collect: 0,
+            mutations: 0,
+            sweep: None,
         };
         g.branches
             .insert(0, microstack::Stack::from_vec([0].to_vec()));
@@ -49,11 +99,169 @@ impl<const N: usize> Sodg<N> {
             .insert(1, microstack::Stack::from_vec([0].to_vec()));
         g
     }
+
+    /// Make a new [`Sodg`] of the given capacity and deploy
+    /// a [`Script`] to it.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Sodg;
+    /// let mut g : Sodg<16> = Sodg::from_script(
+    ///     "ADD(0); ADD(1); BIND(0, 1, foo); PUT(1, 2A);",
+    ///     256,
+    /// ).unwrap();
+    /// assert_eq!(1, g.kid_str(0, "foo").unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the script can't be deployed, an error will be returned.
+    pub fn from_script(txt: &str, cap: usize) -> Result<Self> {
+        let mut g = Self::empty(cap);
+        Script::from_str(txt)
+            .deploy_to(&mut g)
+            .context("Can't deploy the script")?;
+        Ok(g)
+    }
+
+    /// Make a new [`Sodg`] of the given capacity from a list of edges,
+    /// adding all vertices they refer to.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let g : Sodg<16> = Sodg::from_edges(
+    ///     256,
+    ///     [(0, 1, Label::Alpha(0)), (1, 2, Label::Alpha(0))],
+    /// );
+    /// assert_eq!(3, g.len());
+    /// assert_eq!(Some(2), g.kid(1, Label::Alpha(0)));
+    /// ```
+    ///
+    /// The vertex `ν0` is always added, even if no edges refer to it.
+    /// All vertices are added first, and then the edges are bound
+    /// in the order they are provided.
+    ///
+    /// # Panics
+    ///
+    /// If a vertex is out of capacity, it will panic.
+    #[must_use]
+    pub fn from_edges(cap: usize, edges: impl IntoIterator<Item = (usize, usize, Label)>) -> Self {
+        let mut g = Self::empty(cap);
+        g.add(0);
+        let edges: Vec<(usize, usize, Label)> = edges.into_iter().collect();
+        for (v1, v2, _) in &edges {
+            g.add(*v1);
+            g.add(*v2);
+        }
+        for (v1, v2, a) in edges {
+            g.bind(v1, v2, a);
+        }
+        g
+    }
+}
+
+/// The smallest capacity of a [`Sodg`] made by [`Sodg::from_iter`].
+const MIN_CAP: usize = 256;
+
+impl<const N: usize> FromIterator<(usize, usize, Label)> for Sodg<N> {
+    /// Make a new [`Sodg`] from edges, just like [`Sodg::from_edges`] does.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let g : Sodg<16> = [(0, 1, Label::Alpha(0))].into_iter().collect();
+    /// assert_eq!(Some(1), g.kid(0, Label::Alpha(0)));
+    /// ```
+    ///
+    /// The capacity is enough for the largest vertex ID met,
+    /// but not less than 256.
+    fn from_iter<I: IntoIterator<Item = (usize, usize, Label)>>(iter: I) -> Self {
+        let edges: Vec<(usize, usize, Label)> = iter.into_iter().collect();
+        let cap = edges
+            .iter()
+            .map(|(v1, v2, _)| v1.max(v2) + 1)
+            .fold(MIN_CAP, usize::max);
+        Self::from_edges(cap, edges)
+    }
 }
 
+#[cfg(test)]
+use std::str::FromStr;
+
 #[test]
 fn makes_an_empty_sodg() {
     let mut g: Sodg<16> = Sodg::empty(256);
     g.add(0);
     assert_eq!(1, g.len());
 }
+
+#[test]
+fn makes_sodg_from_script() {
+    let mut g: Sodg<16> = Sodg::from_script(
+        "
+        ADD(0);  ADD($ν1); # adding two vertices
+        BIND(ν0, $ν1, foo  );
+        PUT($ν1  , d0-bf-D1-80-d0-B8-d0-b2-d0-b5-d1-82);
+        ",
+        256,
+    )
+    .unwrap();
+    assert_eq!(2, g.len());
+    assert_eq!("привет", g.data(1).unwrap().to_utf8().unwrap());
+    assert_eq!(1, g.kid_str(0, "foo").unwrap());
+    assert!(Sodg::<16>::from_script("BIND(0, 1);", 256).is_err());
+}
+
+#[test]
+fn makes_sodg_from_edges() {
+    let g: Sodg<16> = Sodg::from_edges(
+        256,
+        [
+            (0, 1, Label::Alpha(0)),
+            (0, 2, Label::Alpha(1)),
+            (2, 3, Label::Greek('ρ')),
+        ],
+    );
+    let mut manual: Sodg<16> = Sodg::empty(256);
+    for v in 0..4 {
+        manual.add(v);
+    }
+    manual.bind(0, 1, Label::Alpha(0));
+    manual.bind(0, 2, Label::Alpha(1));
+    manual.bind(2, 3, Label::Greek('ρ'));
+    assert_eq!(manual.to_xml_full().unwrap(), g.to_xml_full().unwrap());
+    assert_eq!(4, g.len());
+    assert_eq!(1, Sodg::<16>::from_edges(256, []).len());
+}
+
+#[test]
+fn collects_sodg_from_edges() {
+    let g: Sodg<16> = vec![
+        (0, 1, Label::Alpha(0)),
+        (1, 2, Label::from_str("foo").unwrap()),
+        (0, 1000, Label::Alpha(1)),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(4, g.len());
+    assert_eq!(Some(1), g.kid(0, Label::Alpha(0)));
+    assert_eq!(Some(2), g.kid_str(1, "foo"));
+    assert_eq!(Some(1000), g.kid(0, Label::Alpha(1)));
+}
+
+#[test]
+fn makes_sodg_with_room_for_branches() {
+    let mut g: Sodg<16> = Sodg::empty_with(256, 64);
+    assert_eq!(64, g.branches.capacity());
+    for v in 0..50 {
+        g.add(v * 2);
+        g.add(v * 2 + 1);
+        g.bind(v * 2, v * 2 + 1, Label::Alpha(0));
+    }
+    assert_eq!(64, g.branches.capacity());
+    assert_eq!(2, Sodg::<16>::empty_with(256, 0).branches.capacity());
+}
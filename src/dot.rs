@@ -18,8 +18,10 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::{Label, Persistence, Sodg};
+use crate::{Dot, Hex, Label, Persistence, Sodg, Vertex};
 use itertools::Itertools;
+use std::fmt;
+use std::fmt::{Display, Formatter, Write};
 
 impl<const N: usize> Sodg<N> {
     /// Print SODG as a DOT graph.
@@ -52,71 +54,323 @@ impl<const N: usize> Sodg<N> {
     /// ```
     #[must_use]
     pub fn to_dot(&self) -> String {
-        let mut lines: Vec<String> = vec![];
-        lines.push(
-            "/* Render it at https://dreampuf.github.io/GraphvizOnline/ */
-digraph {
-  node [fixedsize=true,width=1,fontname=\"Arial\"];
-  edge [fontname=\"Arial\"];"
-                .to_string(),
-        );
+        self.dot().to_string()
+    }
+
+    /// Make a printable DOT graph, without rendering it into a [`String`].
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Sodg;
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// println!("{}", g.dot());
+    /// assert_eq!(g.to_dot(), g.dot().to_string());
+    /// ```
+    #[must_use]
+    pub const fn dot(&self) -> Dot<'_, N> {
+        Dot(self)
+    }
+
+    /// Print SODG as a DOT graph, with a stable comment above each edge.
+    ///
+    /// The comment looks like `// edge v0-foo-v1` and doesn't depend on
+    /// anything but the edge itself, which makes it easy to line up
+    /// two renders of the same graph, for example:
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::from_str("foo").unwrap());
+    /// assert!(g.to_dot_annotated().contains("// edge v0-foo-v1\n"));
+    /// ```
+    #[must_use]
+    pub fn to_dot_annotated(&self) -> String {
+        let mut out = String::new();
+        let _ = self.print_dot(&mut out, true, false, |v| v);
+        out
+    }
+
+    /// Print SODG as a DOT graph, with each weakly connected component
+    /// of it in its own cluster.
+    ///
+    /// Two vertices are in the same component if there is a path between
+    /// them, no matter the direction of edges. For example, here are two
+    /// components, `cluster_c0` with `ν0` and `ν1`, and `cluster_c1`
+    /// with `ν2` alone:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.add(2);
+    /// g.bind(1, 0, Label::Alpha(0));
+    /// let dot = g.to_dot_components();
+    /// assert!(dot.contains("subgraph cluster_c0 {\n    v0["));
+    /// assert!(dot.contains("subgraph cluster_c1 {\n    v2["));
+    /// ```
+    ///
+    /// It helps to spot islands of vertices that are not connected
+    /// to the rest of the graph.
+    #[must_use]
+    pub fn to_dot_components(&self) -> String {
+        let mut out = String::new();
+        let _ = self.print_dot_components(&mut out);
+        out
+    }
+
+    /// Print SODG as a DOT graph, just like [`Sodg::to_dot`] does, but with
+    /// vertices ordered by the key provided, instead of their IDs.
+    ///
+    /// For example, this puts vertices with more edges first:
+    ///
+    /// ```
+    /// use std::cmp::Reverse;
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(1, 0, Label::Alpha(0));
+    /// let dot = g.to_dot_ordered(|v| Reverse(g.kids(v).count()));
+    /// assert!(dot.find("v1[").unwrap() < dot.find("v0[").unwrap());
+    /// ```
+    ///
+    /// Vertices with equal keys are ordered by their IDs.
+    #[must_use]
+    pub fn to_dot_ordered<K: Ord>(&self, key: impl Fn(usize) -> K) -> String {
+        let mut out = String::new();
+        let _ = self.print_dot(&mut out, false, false, key);
+        out
+    }
+
+    /// Print SODG as a DOT graph, marking what matters for garbage collection.
+    ///
+    /// The root vertex `ν0` is double-circled, vertices with data not taken
+    /// yet are orange, while vertices with data already taken are gray,
+    /// for example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.put(1, &Hex::from(42));
+    /// g.data(1);
+    /// let dot = g.to_dot_gc();
+    /// assert!(dot.contains("v0[shape=doublecircle,"));
+    /// assert!(dot.contains("v1[shape=circle,label=\"ν1\",style=filled,fillcolor=lightgray]"));
+    /// ```
+    #[must_use]
+    pub fn to_dot_gc(&self) -> String {
+        let mut out = String::new();
+        let _ = self.print_dot(&mut out, false, true, |v| v);
+        out
+    }
+
+    /// Print SODG as a DOT graph, showing the data of vertices
+    /// as records of fields.
+    ///
+    /// The `decode` function turns the data of a vertex into a list of
+    /// field names and values, which are rendered as a `record` node.
+    /// Vertices without data stay circles. For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, HexReader, HexWriter, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.put(0, &HexWriter::new().push_i64(42).finish());
+    /// let dot = g.to_dot_records(|d| {
+    ///     let mut r = HexReader::new(d);
+    ///     vec![("x".to_string(), r.read_i64().unwrap().to_string())]
+    /// });
+    /// assert!(dot.contains("v0[shape=record,fixedsize=false,label=\"{ν0|x: 42}\"];"));
+    /// ```
+    ///
+    /// The chars that are special in records, like `|` or `{`,
+    /// are escaped in names and values.
+    #[must_use]
+    pub fn to_dot_records(&self, decode: impl Fn(&Hex) -> Vec<(String, String)>) -> String {
+        let mut out = String::new();
+        let _ = self.print_dot_records(&mut out, decode);
+        out
+    }
+
+    /// Print SODG as a DOT graph to the writer, with records of fields.
+    fn print_dot_records<W: Write>(
+        &self,
+        w: &mut W,
+        decode: impl Fn(&Hex) -> Vec<(String, String)>,
+    ) -> fmt::Result {
+        w.write_str(DOT_HEADER)?;
+        for (v, vtx) in self.vertices.iter().sorted_by_key(|(v, _)| *v) {
+            if vtx.persistence == Persistence::Empty {
+                Self::print_dot_vertex(w, v, vtx, false, false, "  ")?;
+                continue;
+            }
+            write!(w, "\n  v{v}[shape=record,fixedsize=false,label=\"{{ν{v}")?;
+            for (k, x) in decode(&vtx.data) {
+                write!(w, "|{}: {}", record_escape(&k), record_escape(&x))?;
+            }
+            w.write_str("}\"];")?;
+            Self::print_dot_edges(w, v, vtx, false, "  ")?;
+        }
+        w.write_str("\n}\n")
+    }
+
+    /// Print SODG as a DOT graph to the writer, with or without edge annotations,
+    /// with or without garbage collection marks, ordering vertices by the key.
+    fn print_dot<W: Write, K: Ord>(
+        &self,
+        w: &mut W,
+        annotated: bool,
+        gc: bool,
+        key: impl Fn(usize) -> K,
+    ) -> fmt::Result {
+        w.write_str(DOT_HEADER)?;
         for (v, vtx) in self
             .vertices
             .iter()
-            .sorted_by_key(|(v, _)| <usize>::clone(v))
+            .sorted_by(|(a, _), (b, _)| key(*a).cmp(&key(*b)).then(a.cmp(b)))
         {
-            lines.push(format!(
-                "  v{v}[shape=circle,label=\"ν{v}\"{}]; {}",
-                if vtx.persistence == Persistence::Empty {
-                    ""
-                } else {
-                    ",color=\"#f96900\""
-                },
-                if vtx.persistence == Persistence::Empty {
-                    String::new()
-                } else {
-                    format!("/* {} */", vtx.data)
-                },
-            ));
-            for e in vtx.edges.iter().sorted_by_key(|e| e.0) {
-                lines.push(format!(
-                    "  v{v} -> v{} [label=\"{}\"{}{}];",
-                    e.1,
-                    e.0,
-                    match e.0 {
-                        Label::Greek(g) => {
-                            if *g == 'ρ' || *g == 'σ' {
-                                ",color=gray,fontcolor=gray"
-                            } else {
-                                ""
-                            }
-                        }
-                        _ => {
-                            ""
-                        }
-                    },
-                    match e.0 {
-                        Label::Greek(g) => {
-                            if *g == 'π' {
-                                ",style=dashed"
-                            } else {
-                                ""
-                            }
-                        }
-                        _ => {
-                            ""
-                        }
+            Self::print_dot_vertex(w, v, vtx, annotated, gc, "  ")?;
+        }
+        w.write_str("\n}\n")
+    }
+
+    /// Print SODG as a DOT graph to the writer, one cluster per component.
+    fn print_dot_components<W: Write>(&self, w: &mut W) -> fmt::Result {
+        let vx: Vec<usize> = self.keys().into_iter().sorted().collect();
+        let mut roots: Vec<usize> = (0..vx.len()).collect();
+        for (i, v) in vx.iter().enumerate() {
+            for (_, to) in self.kids(*v) {
+                if let Ok(j) = vx.binary_search(to) {
+                    let (a, b) = (Self::dot_root(&mut roots, i), Self::dot_root(&mut roots, j));
+                    roots[a.max(b)] = a.min(b);
+                }
+            }
+        }
+        w.write_str(DOT_HEADER)?;
+        let mut clusters = 0;
+        for (i, first) in vx.iter().enumerate() {
+            if Self::dot_root(&mut roots, i) != i {
+                continue;
+            }
+            write!(w, "\n  subgraph cluster_c{clusters} {{")?;
+            for (j, v) in vx.iter().enumerate().skip(i) {
+                if Self::dot_root(&mut roots, j) == i {
+                    if let Some(vtx) = self.vertices.get(*v) {
+                        Self::print_dot_vertex(w, *v, vtx, false, false, "    ")?;
                     }
-                ));
+                }
             }
+            write!(w, "\n  }} /* cluster_c{clusters} starts at ν{first} */")?;
+            clusters += 1;
         }
-        lines.push("}\n".to_string());
-        lines.join("\n")
+        w.write_str("\n}\n")
+    }
+
+    /// Find the root of the set with the element, in a union-find forest.
+    fn dot_root(roots: &mut [usize], i: usize) -> usize {
+        let mut r = i;
+        while roots[r] != r {
+            r = roots[r];
+        }
+        roots[i] = r;
+        r
+    }
+
+    /// Print one vertex and its edges, with the given indentation.
+    fn print_dot_vertex<W: Write>(
+        w: &mut W,
+        v: usize,
+        vtx: &Vertex<N>,
+        annotated: bool,
+        gc: bool,
+        indent: &str,
+    ) -> fmt::Result {
+        write!(
+            w,
+            "\n{indent}v{v}[shape={},label=\"ν{v}\"{}]; {}",
+            if gc && v == 0 {
+                "doublecircle"
+            } else {
+                "circle"
+            },
+            match vtx.persistence {
+                Persistence::Empty => "",
+                Persistence::Taken if gc => ",style=filled,fillcolor=lightgray",
+                _ => ",color=\"#f96900\"",
+            },
+            if vtx.persistence == Persistence::Empty {
+                String::new()
+            } else {
+                format!("/* {} */", vtx.data)
+            },
+        )?;
+        Self::print_dot_edges(w, v, vtx, annotated, indent)
+    }
+
+    /// Print the edges of one vertex, with the given indentation.
+    fn print_dot_edges<W: Write>(
+        w: &mut W,
+        v: usize,
+        vtx: &Vertex<N>,
+        annotated: bool,
+        indent: &str,
+    ) -> fmt::Result {
+        for e in vtx.edges.iter().sorted_by_key(|e| e.0) {
+            if annotated {
+                write!(w, "\n{indent}// edge v{v}-{}-v{}", e.0, e.1)?;
+            }
+            write!(
+                w,
+                "\n{indent}v{v} -> v{} [label=\"{}\"{}{}];",
+                e.1,
+                e.0,
+                match e.0 {
+                    Label::Greek(g) if *g == 'ρ' || *g == 'σ' => {
+                        ",color=gray,fontcolor=gray"
+                    }
+                    _ => "",
+                },
+                match e.0 {
+                    Label::Greek(g) if *g == 'π' => ",style=dashed",
+                    _ => "",
+                }
+            )?;
+        }
+        Ok(())
     }
 }
 
-#[cfg(test)]
-use crate::Hex;
+/// Escape the chars that have a meaning inside a DOT record label.
+fn record_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '{' | '}' | '|' | '<' | '>' | '"' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// The beginning of every DOT graph.
+const DOT_HEADER: &str = "/* Render it at https://dreampuf.github.io/GraphvizOnline/ */
+digraph {
+  node [fixedsize=true,width=1,fontname=\"Arial\"];
+  edge [fontname=\"Arial\"];";
+
+impl<const N: usize> Display for Dot<'_, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.print_dot(f, false, false, |v| v)
+    }
+}
 
 #[test]
 fn simple_graph_to_dot() {
@@ -128,3 +382,110 @@ fn simple_graph_to_dot() {
     let dot = g.to_dot();
     assert!(dot.contains("shape=circle,label=\"ν0\""));
 }
+
+#[test]
+fn annotates_edges() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.bind(1, 0, Label::Greek('ρ'));
+    let dot = g.to_dot_annotated();
+    assert!(dot.contains("  // edge v0-α0-v1\n  v0 -> v1"));
+    assert!(dot.contains("  // edge v1-ρ-v0\n"));
+    assert_eq!(dot, g.to_dot_annotated());
+    assert!(!g.to_dot().contains("// edge"));
+}
+
+#[test]
+fn writes_dot_through_wrapper() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.put(0, &Hex::from_str_bytes("hello"));
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    let mut out = String::new();
+    write!(out, "{}", g.dot()).unwrap();
+    assert_eq!(g.to_dot(), out);
+}
+
+#[test]
+fn clusters_disjoint_trees() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    for v in 0..6 {
+        g.add(v);
+    }
+    g.bind(0, 1, Label::Alpha(0));
+    g.bind(0, 2, Label::Alpha(1));
+    g.bind(3, 4, Label::Alpha(0));
+    g.bind(5, 4, Label::Alpha(0));
+    let dot = g.to_dot_components();
+    assert_eq!(2, dot.matches("subgraph cluster_c").count());
+    assert!(dot.contains("cluster_c0 starts at ν0"));
+    assert!(dot.contains("cluster_c1 starts at ν3"));
+    let second = dot.split("subgraph cluster_c1").nth(1).unwrap();
+    assert!(second.contains("v5 -> v4"));
+    assert!(!second.contains("v0["));
+}
+
+#[test]
+fn orders_vertices_by_degree() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    for v in 0..4 {
+        g.add(v);
+    }
+    g.bind(2, 0, Label::Alpha(0));
+    g.bind(2, 1, Label::Alpha(1));
+    g.bind(2, 3, Label::Alpha(2));
+    g.bind(3, 0, Label::Alpha(0));
+    let dot = g.to_dot_ordered(|v| std::cmp::Reverse(g.kids(v).count()));
+    let pos = |v: usize| dot.find(&format!("\n  v{v}[")).unwrap();
+    assert!(pos(2) < pos(3));
+    assert!(pos(3) < pos(0));
+    assert!(pos(0) < pos(1));
+    assert_eq!(g.to_dot(), g.to_dot_ordered(|v| v));
+}
+
+#[test]
+fn marks_root_and_taken_vertices() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.add(2);
+    g.bind(0, 1, Label::Alpha(0));
+    g.bind(0, 2, Label::Alpha(1));
+    g.put(1, &Hex::from(1));
+    g.put(2, &Hex::from(2));
+    g.data(1);
+    let dot = g.to_dot_gc();
+    let line = |v: usize| {
+        dot.lines()
+            .find(|l| l.starts_with(&format!("  v{v}[")))
+            .unwrap()
+            .to_string()
+    };
+    assert!(line(0).contains("doublecircle"));
+    assert!(line(1).contains("fillcolor=lightgray"));
+    assert!(line(2).contains("#f96900"));
+    assert!(!line(2).contains("lightgray"));
+    assert!(!g.to_dot().contains("doublecircle"));
+}
+
+#[test]
+fn renders_data_as_records() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.put(1, &Hex::from(42));
+    let dot = g.to_dot_records(|d| {
+        vec![
+            ("value".to_string(), d.to_i64().unwrap().to_string()),
+            ("kind".to_string(), "a|b".to_string()),
+        ]
+    });
+    assert!(dot.contains("v1[shape=record,"), "{dot}");
+    assert!(dot.contains("|value: 42|kind: a\\|b}"), "{dot}");
+    assert!(dot.contains("v0[shape=circle,"), "{dot}");
+    assert!(dot.contains("v0 -> v1 [label=\"α0\"];"), "{dot}");
+}
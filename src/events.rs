@@ -0,0 +1,93 @@
+// Copyright (c) 2022-2025 Objectionary.com
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included
+// in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{Event, Sodg};
+
+impl<const N: usize> Sodg<N> {
+    /// Register a listener of all changes made to the graph.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Event, Sodg};
+    /// fn log(e: &Event) {
+    ///     println!("{e:?}");
+    /// }
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.on_change(log);
+    /// g.add(0);
+    /// ```
+    ///
+    /// Only one listener may be registered, the previous one is replaced.
+    /// The listener is not saved by [`Sodg::save`].
+    pub fn on_change(&mut self, cb: fn(&Event)) {
+        self.listener = Some(cb);
+    }
+
+    /// Report a change to the listener, if it is registered.
+    #[inline]
+    pub(crate) fn emit(&self, e: impl FnOnce() -> Event) {
+        if let Some(cb) = self.listener {
+            cb(&e());
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::{Hex, Label};
+
+#[cfg(test)]
+use std::cell::RefCell;
+
+#[cfg(test)]
+thread_local! {
+    static EVENTS: RefCell<Vec<Event>> = const { RefCell::new(vec![]) };
+}
+
+#[cfg(test)]
+fn record(e: &Event) {
+    EVENTS.with(|v| v.borrow_mut().push(e.clone()));
+}
+
+#[test]
+fn reports_changes() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.on_change(record);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.put(1, &Hex::from(42));
+    g.take(1);
+    g.add(5);
+    g.collect();
+    assert_eq!(
+        vec![
+            Event::Added(0),
+            Event::Added(1),
+            Event::Bound(0, 1, Label::Alpha(0)),
+            Event::Put(1),
+            Event::Took(1),
+            Event::Collected(vec![0, 1]),
+            Event::Added(5),
+            Event::Collected(vec![5]),
+        ],
+        EVENTS.with(|v| v.borrow().clone())
+    );
+}
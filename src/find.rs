@@ -0,0 +1,432 @@
+// Copyright (c) 2022-2025 Objectionary.com
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included
+// in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{DeadRelay, FindCache, Label, LambdaRelay, Relay, Sodg};
+use anyhow::{anyhow, Context, Result};
+#[cfg(debug_assertions)]
+use log::trace;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+impl Relay for DeadRelay {
+    /// Always return an error, never finding anything.
+    fn re(&self, v: usize, a: &Label) -> Result<String> {
+        Err(anyhow!("Can't find ν{v}.{a}, the relay is dead"))
+    }
+}
+
+impl LambdaRelay {
+    /// Make a new [`LambdaRelay`] from a function.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use sodg::{Label, LambdaRelay, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::from_str("foo").unwrap());
+    /// let relay = LambdaRelay::new(|_v, a| {
+    ///     assert_eq!("bar", a.to_string());
+    ///     Ok("foo".to_string())
+    /// });
+    /// assert_eq!(1, g.find(0, "bar", &relay).unwrap());
+    /// ```
+    #[must_use]
+    pub fn new(lambda: fn(usize, &Label) -> Result<String>) -> Self {
+        Self { lambda }
+    }
+}
+
+impl Relay for LambdaRelay {
+    /// Call the lambda function.
+    fn re(&self, v: usize, a: &Label) -> Result<String> {
+        (self.lambda)(v, a)
+    }
+}
+
+impl<const N: usize> Sodg<N> {
+    /// Find a vertex by the locator, starting from vertex `v1`.
+    ///
+    /// The locator is a list of labels separated by dots, for example
+    /// `foo.bar.α0`. A segment like `ν42` makes a jump to the vertex with
+    /// this ID. For example:
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use sodg::{DeadRelay, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::from_str("foo").unwrap());
+    /// g.add(2);
+    /// g.bind(1, 2, Label::from_str("bar").unwrap());
+    /// assert_eq!(2, g.find(0, "foo.bar", &DeadRelay::default()).unwrap());
+    /// assert_eq!(2, g.find(0, "ν1.bar", &DeadRelay::default()).unwrap());
+    /// ```
+    ///
+    /// Two dots, like in `foo..bar`, mean a jump to the parent, along
+    /// the `ρ` edge, mirroring the `..` of a file system:
+    ///
+    /// ```
+    /// use sodg::{DeadRelay, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// g.bind(1, 0, Label::Greek('ρ'));
+    /// assert_eq!(0, g.find(0, "α0..", &DeadRelay::default()).unwrap());
+    /// ```
+    ///
+    /// A leading slash, like in `/foo.bar`, means that the search starts
+    /// from the root, which is the vertex zero, no matter what `v1` is:
+    ///
+    /// ```
+    /// use sodg::{DeadRelay, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// assert_eq!(1, g.find(1, "/α0", &DeadRelay::default()).unwrap());
+    /// ```
+    ///
+    /// A dot inside a label must be escaped with a backslash, for example
+    /// `a\.b.c` means the label `a.b` followed by the label `c`, while
+    /// `\\` means a single backslash.
+    ///
+    /// If an attribute is missing, the `relay` is asked for the locator
+    /// to follow instead of it, starting from the same vertex.
+    ///
+    /// # Errors
+    ///
+    /// If the vertex can't be found, an error will be returned.
+    pub fn find<T: Relay>(&self, v1: usize, loc: &str, relay: &T) -> Result<usize> {
+        if let Some(cache) = &self.finds {
+            let mut c = cache.borrow_mut();
+            if let Some(v) = c.resolved.get(&(v1, loc.to_string())).copied() {
+                c.hits += 1;
+                return Ok(v);
+            }
+        }
+        let mut relays = vec![];
+        let v = self.find_with_indent(v1, loc, relay, 0, &mut relays)?;
+        if relays.is_empty() {
+            if let Some(cache) = &self.finds {
+                cache.borrow_mut().resolved.insert((v1, loc.to_string()), v);
+            }
+        }
+        Ok(v)
+    }
+
+    /// Find a vertex by the locator, just like [`Sodg::find`] does, and
+    /// also return the decisions made by the relay on the way.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, LambdaRelay, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// let relay = LambdaRelay::new(|_, _| Ok("α0".to_string()));
+    /// let (v, relays) = g.find_traced(0, "foo", &relay).unwrap();
+    /// assert_eq!(1, v);
+    /// assert_eq!(vec!["ν0.foo → re 'α0'"], relays);
+    /// ```
+    ///
+    /// The cache of [`Sodg::enable_find_cache`] is not used.
+    ///
+    /// # Errors
+    ///
+    /// If the vertex can't be found, an error will be returned.
+    pub fn find_traced<T: Relay>(
+        &self,
+        v1: usize,
+        loc: &str,
+        relay: &T,
+    ) -> Result<(usize, Vec<String>)> {
+        let mut relays = vec![];
+        let v = self.find_with_indent(v1, loc, relay, 0, &mut relays)?;
+        Ok((v, relays))
+    }
+
+    /// Start caching the results of [`Sodg::find`].
+    ///
+    /// Only the locators resolved without the help of a relay are cached.
+    /// The cache is cleared on every change of edges. For example:
+    ///
+    /// ```
+    /// use sodg::{DeadRelay, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.enable_find_cache();
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// g.find(0, "α0", &DeadRelay::default()).unwrap();
+    /// g.find(0, "α0", &DeadRelay::default()).unwrap();
+    /// assert_eq!(1, g.find_cache_hits());
+    /// ```
+    pub fn enable_find_cache(&mut self) {
+        if self.finds.is_none() {
+            self.finds = Some(RefCell::new(FindCache::default()));
+        }
+    }
+
+    /// How many times [`Sodg::find`] took the result from the cache.
+    #[must_use]
+    pub fn find_cache_hits(&self) -> usize {
+        self.finds.as_ref().map_or(0, |c| c.borrow().hits)
+    }
+
+    /// Forget all locators resolved by [`Sodg::find`] so far.
+    #[inline]
+    pub(crate) fn forget_finds(&self) {
+        if let Some(cache) = &self.finds {
+            cache.borrow_mut().resolved.clear();
+        }
+    }
+
+    /// Find a vertex, printing the trace with the given indentation
+    /// and recording every decision of the relay.
+    fn find_with_indent<T: Relay>(
+        &self,
+        v1: usize,
+        loc: &str,
+        relay: &T,
+        depth: usize,
+        relays: &mut Vec<String>,
+    ) -> Result<usize> {
+        walk(&|v, a| self.kid(v, a), v1, loc, relay, depth, relays)
+    }
+}
+
+/// Find a vertex by the locator, taking the kids of vertices from `kid`,
+/// printing the trace with the given indentation and recording every
+/// decision of the relay.
+///
+/// This is what [`Sodg::find`] does, and what [`FrozenSodg::find`] does too.
+#[cfg_attr(
+    not(debug_assertions),
+    allow(clippy::useless_let_if_seq, clippy::only_used_in_recursion)
+)]
+pub fn walk<T: Relay>(
+    kid: &dyn Fn(usize, Label) -> Option<usize>,
+    v1: usize,
+    loc: &str,
+    relay: &T,
+    depth: usize,
+    relays: &mut Vec<String>,
+) -> Result<usize> {
+    #[cfg(debug_assertions)]
+    let indent = "  ".repeat(depth);
+    let mut v = v1;
+    let mut path = loc;
+    if let Some(rest) = loc.strip_prefix(ROOT) {
+        v = 0;
+        path = rest;
+        #[cfg(debug_assertions)]
+        trace!("#find: {indent}started from the root ν{v}");
+    }
+    let mut locator: VecDeque<String> = segments(path).into();
+    while let Some(k) = locator.pop_front() {
+        if let Some(num) = k.strip_prefix('ν') {
+            v = usize::from_str(num).with_context(|| format!("Can't parse '{k}'"))?;
+            #[cfg(debug_assertions)]
+            trace!("#find: {indent}jumped to ν{v}");
+            continue;
+        }
+        if k == PARENT {
+            let up = kid(v, Label::Greek('ρ'))
+                .with_context(|| format!("Can't go up from ν{v}, there is no ρ edge"))?;
+            #[cfg(debug_assertions)]
+            trace!("#find: {indent}ν{v}.ρ → ν{up}");
+            v = up;
+            continue;
+        }
+        let a = Label::from_str(k.as_str())?;
+        if let Some(to) = kid(v, a) {
+            #[cfg(debug_assertions)]
+            trace!("#find: {indent}ν{v}.{a} → ν{to}");
+            v = to;
+            continue;
+        }
+        let re = relay
+            .re(v, &a)
+            .with_context(|| format!("Can't find ν{v}.{a}"))?;
+        let step = format!("ν{v}.{a} → re '{re}'");
+        #[cfg(debug_assertions)]
+        trace!("#find: {indent}{step}");
+        relays.push(step);
+        v = walk(kid, v, re.as_str(), relay, depth + 1, relays)
+            .with_context(|| format!("Can't find ν{v}.{a} relayed to '{re}'"))?;
+    }
+    #[cfg(debug_assertions)]
+    trace!("#find: {indent}found ν{v1}.{loc} → ν{v}");
+    Ok(v)
+}
+
+/// The segment of a locator that means a jump to the parent.
+const PARENT: &str = "..";
+
+/// The prefix of a locator that means a start from the root.
+const ROOT: char = '/';
+
+/// Split the locator into segments by dots, un-escaping
+/// the dots and backslashes escaped with a backslash.
+///
+/// Two dots in a row make a segment of their own, see [`PARENT`].
+fn segments(loc: &str) -> Vec<String> {
+    let mut all = vec![];
+    let mut seg = String::new();
+    let mut chars = loc.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(n @ ('.' | '\\')) => seg.push(n),
+                Some(n) => {
+                    seg.push(c);
+                    seg.push(n);
+                }
+                None => seg.push(c),
+            },
+            '.' => {
+                all.push(std::mem::take(&mut seg));
+                if chars.clone().next() == Some('.') {
+                    chars.next();
+                    all.push(PARENT.to_string());
+                }
+            }
+            _ => seg.push(c),
+        }
+    }
+    all.push(seg);
+    all.retain(|s| !s.is_empty());
+    all
+}
+
+#[test]
+fn finds_simple_path() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("foo").unwrap());
+    g.add(2);
+    g.bind(1, 2, Label::Alpha(0));
+    assert_eq!(2, g.find(0, "foo.α0", &DeadRelay::default()).unwrap());
+    assert_eq!(0, g.find(0, "", &DeadRelay::default()).unwrap());
+    assert!(g.find(0, "bar", &DeadRelay::default()).is_err());
+}
+
+#[test]
+fn finds_with_relay() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("foo").unwrap());
+    g.add(2);
+    g.bind(1, 2, Label::from_str("bar").unwrap());
+    let relay = LambdaRelay::new(|_, _| Ok("ν1.bar".to_string()));
+    assert_eq!(2, g.find(0, "xyz", &relay).unwrap());
+}
+
+#[test]
+fn caches_found_locators() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.enable_find_cache();
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("foo").unwrap());
+    assert_eq!(1, g.find(0, "foo", &DeadRelay::default()).unwrap());
+    assert_eq!(0, g.find_cache_hits());
+    assert_eq!(1, g.find(0, "foo", &DeadRelay::default()).unwrap());
+    assert_eq!(1, g.find_cache_hits());
+    g.add(2);
+    g.bind(0, 2, Label::from_str("foo").unwrap());
+    assert_eq!(2, g.find(0, "foo", &DeadRelay::default()).unwrap());
+    assert_eq!(1, g.find_cache_hits());
+    let relay = LambdaRelay::new(|_, _| Ok("foo".to_string()));
+    g.find(0, "bar", &relay).unwrap();
+    g.find(0, "bar", &relay).unwrap();
+    assert_eq!(1, g.find_cache_hits());
+}
+
+#[test]
+fn finds_label_with_dot() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("a.b").unwrap());
+    g.add(2);
+    g.bind(1, 2, Label::from_str("c").unwrap());
+    assert_eq!(2, g.find(0, "a\\.b.c", &DeadRelay::default()).unwrap());
+    assert!(g.find(0, "a.b.c", &DeadRelay::default()).is_err());
+    assert_eq!(vec!["x\\y", "z"], segments("x\\\\y.z"));
+}
+
+#[test]
+fn traces_relay_decisions() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("foo").unwrap());
+    g.add(2);
+    g.bind(1, 2, Label::from_str("bar").unwrap());
+    let relay = LambdaRelay::new(|v, _| Ok(if v == 0 { "foo.xyz" } else { "bar" }.to_string()));
+    let (v, relays) = g.find_traced(0, "abc", &relay).unwrap();
+    assert_eq!(2, v);
+    assert_eq!(vec!["ν0.abc → re 'foo.xyz'", "ν1.xyz → re 'bar'"], relays);
+    let (v, relays) = g.find_traced(0, "foo.bar", &relay).unwrap();
+    assert_eq!(2, v);
+    assert!(relays.is_empty());
+}
+
+#[test]
+fn finds_through_parent() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    for v in 0..3 {
+        g.add(v);
+    }
+    g.bind(0, 1, Label::from_str("child").unwrap());
+    g.bind(0, 2, Label::from_str("foo").unwrap());
+    g.bind(1, 0, Label::Greek('ρ'));
+    assert_eq!(2, g.find(0, "child..foo", &DeadRelay::default()).unwrap());
+    assert_eq!(2, g.find(1, "..foo", &DeadRelay::default()).unwrap());
+    assert!(g.find(0, "foo..", &DeadRelay::default()).is_err());
+    assert_eq!(vec!["a", "..", "b"], segments("a..b"));
+    assert_eq!(vec!["a.", "b"], segments("a\\..b"));
+}
+
+#[test]
+fn finds_absolute_path_from_anywhere() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    for v in 0..4 {
+        g.add(v);
+    }
+    g.bind(0, 1, Label::from_str("a").unwrap());
+    g.bind(1, 2, Label::from_str("b").unwrap());
+    g.bind(2, 3, Label::from_str("c").unwrap());
+    assert_eq!(3, g.find(2, "/a.b.c", &DeadRelay::default()).unwrap());
+    assert_eq!(0, g.find(3, "/", &DeadRelay::default()).unwrap());
+    assert!(g.find(2, "a.b.c", &DeadRelay::default()).is_err());
+    assert!(g.find(2, "/c", &DeadRelay::default()).is_err());
+}
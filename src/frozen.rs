@@ -0,0 +1,247 @@
+// Copyright (c) 2022-2025 Objectionary.com
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included
+// in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::find::walk;
+use crate::{FrozenSodg, Hex, Label, Persistence, Relay, Sodg, Vertex};
+use anyhow::Result;
+use std::sync::Arc;
+
+impl<const N: usize> Sodg<N> {
+    /// Make an immutable snapshot of the graph, which may be
+    /// read from many threads at once.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.put(0, &Hex::from(42));
+    /// let frozen = g.freeze();
+    /// g.put(0, &Hex::from(7));
+    /// let t = std::thread::spawn(move || frozen.peek(0).unwrap().to_i64().unwrap());
+    /// assert_eq!(42, t.join().unwrap());
+    /// ```
+    ///
+    /// The graph is copied, thus later changes of it don't
+    /// affect the snapshot. The listener of changes and the cache
+    /// of [`Sodg::find`] are not copied.
+    #[must_use]
+    pub fn freeze(&self) -> Arc<FrozenSodg<N>> {
+        Arc::new(FrozenSodg {
+            vertices: slots(&self.vertices),
+            branches: slots(&self.branches),
+            stores: slots(&self.stores),
+            next_v: self.next_v,
+            alive: self.alive,
+            symbols: self.symbols.clone(),
+            alerts: self.alerts.clone(),
+            lambdas: self.lambdas.clone(),
+            strict: self.strict,
+            auto_collect: self.auto_collect,
+        })
+    }
+}
+
+impl<const N: usize> FrozenSodg<N> {
+    /// Make a mutable graph out of the snapshot, to use the methods
+    /// of [`Sodg`] that are not available on the snapshot.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// let frozen = g.freeze();
+    /// let mut t = frozen.thaw();
+    /// t.add(1);
+    /// t.bind(0, 1, Label::Alpha(0));
+    /// assert_eq!(2, t.len());
+    /// assert_eq!(1, frozen.len());
+    /// ```
+    ///
+    /// The entire graph is copied, thus it takes as much time
+    /// and memory as [`Sodg::freeze`] does.
+    #[must_use]
+    pub fn thaw(&self) -> Sodg<N> {
+        Sodg {
+            vertices: map(&self.vertices),
+            branches: map(&self.branches),
+            stores: map(&self.stores),
+            next_v: self.next_v,
+            alive: self.alive,
+            listener: None,
+            finds: None,
+            symbols: self.symbols.clone(),
+            alerts: self.alerts.clone(),
+            lambdas: self.lambdas.clone(),
+            strict: self.strict,
+            auto_collect: self.auto_collect,
+            mutations: 0,
+            sweep: None,
+        }
+    }
+
+    /// Get the total number of vertices in the snapshot,
+    /// just like [`Sodg::len`] does.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.alive
+    }
+
+    /// Is the snapshot empty?
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.alive == 0
+    }
+
+    /// Find all kids of a vertex, just like [`Sodg::kids`] does.
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v` is absent, it will panic.
+    pub fn kids(&self, v: usize) -> impl Iterator<Item = (&Label, &usize)> + '_ {
+        self.vertex(v).edges.iter()
+    }
+
+    /// Find a kid of a vertex, by its edge name,
+    /// just like [`Sodg::kid`] does.
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v` is absent, it will panic.
+    #[must_use]
+    pub fn kid(&self, v: usize, a: Label) -> Option<usize> {
+        self.vertex(v).edges.get(&a).copied()
+    }
+
+    /// Read the data of a vertex, just like [`Sodg::peek`] does.
+    ///
+    /// If the vertex has no data, but has a lambda registered by
+    /// [`Sodg::set_lambda`], the lambda is called on a graph made
+    /// by [`FrozenSodg::thaw`], which takes time.
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v` is absent, it will panic.
+    #[must_use]
+    pub fn peek(&self, v: usize) -> Option<Hex> {
+        let vtx = self.vertex(v);
+        if vtx.persistence == Persistence::Empty {
+            self.lambdas.get(&v).map(|f| f(&self.thaw(), v))
+        } else {
+            Some((*vtx.data).clone())
+        }
+    }
+
+    /// Find a vertex by the locator, starting from vertex `v1`,
+    /// just like [`Sodg::find`] does.
+    ///
+    /// # Errors
+    ///
+    /// If the vertex can't be found, an error will be returned.
+    pub fn find<T: Relay>(&self, v1: usize, loc: &str, relay: &T) -> Result<usize> {
+        walk(&|v, a| self.kid(v, a), v1, loc, relay, 0, &mut vec![])
+    }
+
+    /// Print the snapshot as a DOT graph, just like [`Sodg::to_dot`] does.
+    ///
+    /// The graph is made by [`FrozenSodg::thaw`] first.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        self.thaw().to_dot()
+    }
+
+    /// Get the vertex, expecting it to have a slot.
+    fn vertex(&self, v: usize) -> &Vertex<N> {
+        self.vertices[v].as_ref().unwrap()
+    }
+}
+
+/// Copy all slots of a map, empty or not.
+fn slots<V: Clone>(m: &emap::Map<V>) -> Vec<Option<V>> {
+    (0..m.capacity()).map(|i| m.get(i).cloned()).collect()
+}
+
+/// Make a map out of the slots made by [`slots`].
+fn map<V: Clone>(all: &[Option<V>]) -> emap::Map<V> {
+    let mut m = emap::Map::with_capacity_none(all.len());
+    for (i, v) in all.iter().enumerate() {
+        if let Some(v) = v {
+            m.insert(i, v.clone());
+        }
+    }
+    m
+}
+
+#[cfg(test)]
+use crate::DeadRelay;
+
+#[test]
+fn keeps_snapshot_after_mutation() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.enable_find_cache();
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("foo").unwrap());
+    g.put(1, &Hex::from_str_bytes("hello"));
+    let frozen = g.freeze();
+    g.put(1, &Hex::from_str_bytes("bye"));
+    g.unbind(0, Label::from_str("foo").unwrap());
+    g.add(2);
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let f = Arc::clone(&frozen);
+            std::thread::spawn(move || {
+                let v = f.find(0, "foo", &DeadRelay::default()).unwrap();
+                (f.len(), f.peek(v).unwrap().to_utf8().unwrap())
+            })
+        })
+        .collect();
+    for r in readers {
+        assert_eq!((2, "hello".to_string()), r.join().unwrap());
+    }
+    assert!(frozen.to_dot().contains("v0 -> v1"));
+    assert_eq!(3, g.len());
+}
+
+#[cfg(test)]
+use std::str::FromStr;
+
+#[test]
+fn thaws_into_same_graph() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.put(1, &Hex::from(42));
+    let mut t = g.freeze().thaw();
+    assert_eq!(g.to_dot(), t.to_dot());
+    assert_eq!(g.next_id(), t.next_id());
+    assert_eq!(42, t.data(1).unwrap().to_i64().unwrap());
+    assert!(t.check_invariants().is_ok());
+}
+
+#[test]
+fn is_shared_between_threads() {
+    const fn shareable<T: Send + Sync>() {}
+    shareable::<FrozenSodg<16>>();
+}
@@ -0,0 +1,698 @@
+// Copyright (c) 2022-2025 Objectionary.com
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included
+// in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{CollectReport, Event, Persistence, Sodg, Sweep, BRANCH_NONE, BRANCH_STATIC};
+#[cfg(debug_assertions)]
+use log::trace;
+use std::collections::HashSet;
+
+impl<const N: usize> Sodg<N> {
+    /// Collect garbage, treating vertex zero as the only root.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// g.add(2);
+    /// assert_eq!(vec![2], g.collect());
+    /// assert_eq!(2, g.len());
+    /// ```
+    ///
+    /// See [`Sodg::collect_from`] for the details of the algorithm.
+    pub fn collect(&mut self) -> Vec<usize> {
+        self.collect_report().reclaimed
+    }
+
+    /// Collect garbage automatically, after every `every` calls to
+    /// [`Sodg::unbind`], [`Sodg::remove`], and [`Sodg::data`], which are
+    /// the only operations that may turn vertices into garbage.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.set_auto_collect(1);
+    /// g.bind_auto(0, 1, Label::Alpha(0));
+    /// g.bind_auto(0, 2, Label::Alpha(1));
+    /// g.unbind(0, Label::Alpha(0));
+    /// assert_eq!(2, g.len());
+    /// ```
+    ///
+    /// Zero turns it off, which is the default. Mind that a vertex just
+    /// added is garbage until something points to it, so the collection
+    /// may take it away, if [`Sodg::unbind`] happens before it is bound.
+    /// [`Sodg::merge`] never collects in the middle.
+    pub const fn set_auto_collect(&mut self, every: usize) {
+        self.auto_collect = every;
+        self.mutations = 0;
+    }
+
+    /// Count one mutation and collect garbage, if it's time to.
+    pub(crate) fn tick(&mut self) {
+        if self.auto_collect == 0 {
+            return;
+        }
+        self.mutations += 1;
+        if self.mutations >= self.auto_collect {
+            self.mutations = 0;
+            #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+            let removed = self.collect();
+            #[cfg(debug_assertions)]
+            trace!("#tick: {} vertices collected automatically", removed.len());
+        }
+    }
+
+    /// Collect garbage, treating vertex zero as the only root, and
+    /// report what happened in each phase.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// g.add(2);
+    /// g.put(2, &Hex::from(42));
+    /// g.add(3);
+    /// let report = g.collect_report();
+    /// assert_eq!(4, report.scanned);
+    /// assert_eq!(2, report.connected);
+    /// assert_eq!(1, report.busy);
+    /// assert_eq!(vec![3], report.reclaimed);
+    /// ```
+    ///
+    /// See [`Sodg::collect_from`] for the details of the algorithm.
+    pub fn collect_report(&mut self) -> CollectReport {
+        self.collect_with(&[0])
+    }
+
+    /// Collect garbage, treating all `roots` as entry points of the graph.
+    ///
+    /// The collection is done in three phases. First, all vertices reachable
+    /// from any of the `roots` are marked as "connected". Second, all vertices
+    /// holding data not taken yet, or staying in a branch with such data,
+    /// are marked as "busy". Finally, all other vertices are removed from the
+    /// graph. The IDs of removed vertices are returned, in ascending order.
+    ///
+    /// Roots that are absent in the graph are ignored.
+    ///
+    /// # Panics
+    ///
+    /// If any of the `roots` is out of the capacity of the graph, it will panic.
+    pub fn collect_from(&mut self, roots: &[usize]) -> Vec<usize> {
+        self.collect_with(roots).reclaimed
+    }
+
+    /// Collect garbage, treating all `roots` as entry points of the graph,
+    /// and report what happened.
+    ///
+    /// If all vertices are connected, there is nothing to collect,
+    /// and the other phases are skipped.
+    fn collect_with(&mut self, roots: &[usize]) -> CollectReport {
+        let connected = self.reachable(roots);
+        if connected.len() == self.len() {
+            #[cfg(debug_assertions)]
+            trace!(
+                "#collect_from: all {} vertices connected to {} root(s), nothing to collect",
+                connected.len(),
+                roots.len()
+            );
+            return CollectReport {
+                scanned: connected.len(),
+                connected: connected.len(),
+                busy: 0,
+                reclaimed: vec![],
+            };
+        }
+        let (reclaimed, busy) = self.garbage(&connected);
+        let scanned = connected.len() + busy + reclaimed.len();
+        self.erase_all(&reclaimed);
+        if !reclaimed.is_empty() {
+            self.emit(|| Event::Collected(reclaimed.clone()));
+        }
+        #[cfg(debug_assertions)]
+        trace!(
+            "#collect_from: {} vertices reclaimed, {} connected to {} root(s), {busy} busy",
+            reclaimed.len(),
+            connected.len(),
+            roots.len()
+        );
+        CollectReport {
+            scanned,
+            connected: connected.len(),
+            busy,
+            reclaimed,
+        }
+    }
+
+    /// Collect garbage, treating vertex zero and all vertices
+    /// with data not taken yet as roots.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.put(1, &Hex::from(42));
+    /// g.add(2);
+    /// g.bind(1, 2, Label::Alpha(0));
+    /// g.add(3);
+    /// assert_eq!(vec![3], g.collect_preserving_data());
+    /// assert_eq!(3, g.len());
+    /// ```
+    ///
+    /// Unlike [`Sodg::collect`], this never removes a vertex reachable
+    /// from unread data, no matter how the vertices are connected to
+    /// vertex zero and which branches they are in.
+    pub fn collect_preserving_data(&mut self) -> Vec<usize> {
+        let mut roots = vec![0];
+        for (v, vtx) in self.vertices.iter() {
+            if vtx.branch != BRANCH_NONE && vtx.persistence == Persistence::Stored {
+                roots.push(v);
+            }
+        }
+        self.collect_from(&roots)
+    }
+
+    /// Collect garbage, treating vertex zero as the only root, but
+    /// do not more than `budget` units of work.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Sodg;
+    /// let mut g : Sodg<16> = Sodg::empty(4);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.add(2);
+    /// g.add(3);
+    /// assert_eq!((1, false), g.collect_step(3));
+    /// assert_eq!((2, true), g.collect_step(3));
+    /// assert_eq!(1, g.len());
+    /// ```
+    ///
+    /// The function returns the number of vertices reclaimed during this
+    /// step and a flag, which is `true` when the collection is over.
+    /// Calling it many times with a small `budget`, for example once per
+    /// frame, eventually reclaims the same vertices a single call
+    /// to [`Sodg::collect`] would reclaim. The next call after the end
+    /// of a collection starts a new one.
+    ///
+    /// First, the vertices reachable from vertex zero are marked, one vertex
+    /// per unit of work. Then, all vertices are checked one by one, also
+    /// one vertex per unit, and those neither marked nor busy are removed.
+    /// Mind that the vertices found in a step are removed together
+    /// at its end, which costs one more pass over all vertices, to remove
+    /// the edges leading to them.
+    ///
+    /// The graph may be modified between the steps. Vertices added or bound
+    /// to marked ones during the collection are marked too, thus they are
+    /// never removed by it. Vertices that become garbage during
+    /// the collection may survive it, till the next one.
+    ///
+    /// # Panics
+    ///
+    /// May panic if the graph is broken (should never happen, though).
+    pub fn collect_step(&mut self, budget: usize) -> (usize, bool) {
+        let mut sweep = self.sweep.take().unwrap_or_else(|| {
+            let mut s = Sweep::default();
+            if self
+                .vertices
+                .get(0)
+                .is_some_and(|vtx| vtx.branch != BRANCH_NONE)
+            {
+                s.marked.insert(0);
+                s.todo.push(0);
+            }
+            s
+        });
+        let mut reclaimed = vec![];
+        for _ in 0..budget {
+            if let Some(v) = sweep.todo.pop() {
+                for to in self.vertices.get(v).unwrap().edges.values() {
+                    if self.vertices.get(*to).unwrap().branch != BRANCH_NONE
+                        && sweep.marked.insert(*to)
+                    {
+                        sweep.todo.push(*to);
+                    }
+                }
+                continue;
+            }
+            if sweep.cursor >= self.vertices.capacity() {
+                break;
+            }
+            let v = sweep.cursor;
+            sweep.cursor += 1;
+            if sweep.marked.contains(&v) || self.vertices.get(v).unwrap().branch == BRANCH_NONE {
+                continue;
+            }
+            if !self.busy(v) {
+                reclaimed.push(v);
+            }
+        }
+        self.erase_all(&reclaimed);
+        if !reclaimed.is_empty() {
+            self.emit(|| Event::Collected(reclaimed.clone()));
+        }
+        let done = sweep.todo.is_empty() && sweep.cursor >= self.vertices.capacity();
+        #[cfg(debug_assertions)]
+        trace!(
+            "#collect_step: {} vertices reclaimed with a budget of {budget}, {} marked so far{}",
+            reclaimed.len(),
+            sweep.marked.len(),
+            if done { ", done" } else { "" }
+        );
+        if !done {
+            self.sweep = Some(sweep);
+        }
+        (reclaimed.len(), done)
+    }
+
+    /// Mark vertex `v` as reachable, if [`Sodg::collect_step`] is in progress,
+    /// and `from` is either marked too or is not given.
+    pub(crate) fn shade(&mut self, from: Option<usize>, v: usize) {
+        if let Some(s) = &mut self.sweep {
+            if from.is_none_or(|f| s.marked.contains(&f)) && s.marked.insert(v) {
+                s.todo.push(v);
+            }
+        }
+    }
+
+    /// Remove all vertices that are not reachable from vertex zero,
+    /// no matter whether they hold any data or not.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// g.add(2);
+    /// g.put(2, &Hex::from(42));
+    /// assert_eq!(vec![2], g.prune());
+    /// assert_eq!(2, g.len());
+    /// ```
+    ///
+    /// This is simpler and more aggressive than [`Sodg::collect`], which
+    /// keeps busy vertices. The IDs of removed vertices are returned,
+    /// in ascending order.
+    pub fn prune(&mut self) -> Vec<usize> {
+        let reachable = self.reachable(&[0]);
+        let removed: Vec<usize> = self
+            .keys()
+            .into_iter()
+            .filter(|v| !reachable.contains(v))
+            .collect();
+        self.erase_all(&removed);
+        if !removed.is_empty() {
+            self.emit(|| Event::Collected(removed.clone()));
+        }
+        #[cfg(debug_assertions)]
+        trace!(
+            "#prune: {} vertices removed, {} reachable from ν0",
+            removed.len(),
+            reachable.len()
+        );
+        removed
+    }
+
+    /// Find all alive vertices, which are neither `connected` nor busy,
+    /// in ascending order, and count the busy ones.
+    ///
+    /// A vertex is busy when it holds data not taken yet, or stays
+    /// in a branch with such data.
+    fn garbage(&self, connected: &HashSet<usize>) -> (Vec<usize>, usize) {
+        let mut found = vec![];
+        let mut busy = 0;
+        for (v, vtx) in self.vertices.iter() {
+            if vtx.branch == BRANCH_NONE || connected.contains(&v) {
+                continue;
+            }
+            if self.busy(v) {
+                busy += 1;
+            } else {
+                found.push(v);
+            }
+        }
+        (found, busy)
+    }
+
+    /// Is vertex `v` busy, holding data not taken yet, or staying
+    /// in a branch with such data?
+    fn busy(&self, v: usize) -> bool {
+        let vtx = self.vertices.get(v).unwrap();
+        vtx.persistence == Persistence::Stored
+            || (vtx.branch > BRANCH_STATIC && *self.stores.get(vtx.branch).unwrap() > 0)
+    }
+
+    /// Find all alive vertices reachable from any of the `roots`,
+    /// including the roots themselves.
+    fn reachable(&self, roots: &[usize]) -> HashSet<usize> {
+        let mut seen = HashSet::new();
+        let mut todo: Vec<usize> = roots
+            .iter()
+            .copied()
+            .filter(|v| self.vertices.get(*v).unwrap().branch != BRANCH_NONE)
+            .collect();
+        while let Some(v) = todo.pop() {
+            if !seen.insert(v) {
+                continue;
+            }
+            for (_, to) in &self.vertices.get(v).unwrap().edges {
+                if !seen.contains(to) && self.vertices.get(*to).unwrap().branch != BRANCH_NONE {
+                    todo.push(*to);
+                }
+            }
+        }
+        seen
+    }
+}
+
+#[cfg(test)]
+use crate::{Hex, Label};
+
+#[test]
+fn collects_unreachable_vertices() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.add(2);
+    g.add(3);
+    g.bind(2, 3, Label::Alpha(0));
+    assert_eq!(vec![2, 3], g.collect());
+    assert_eq!(2, g.len());
+    assert_eq!(0, g.branches.get(3).unwrap().len());
+}
+
+#[test]
+fn collects_from_many_roots() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.add(5);
+    g.add(6);
+    g.bind(5, 6, Label::Alpha(0));
+    let mut c = g.clone();
+    assert!(c.collect_from(&[0, 5]).is_empty());
+    assert_eq!(4, c.len());
+    assert_eq!(vec![5, 6], g.collect_from(&[0]));
+    assert_eq!(2, g.len());
+    assert_eq!(1, g.kid(0, Label::Alpha(0)).unwrap());
+}
+
+#[test]
+fn keeps_busy_vertices() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.add(2);
+    g.bind(1, 2, Label::Alpha(0));
+    g.put(2, &Hex::from(42));
+    assert!(g.collect().is_empty());
+    assert_eq!(3, g.len());
+    g.data(2);
+    g.add(3);
+    assert_eq!(vec![3], g.collect());
+}
+
+#[test]
+fn prunes_island() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.add(2);
+    g.bind(1, 2, Label::Alpha(0));
+    g.add(7);
+    g.add(8);
+    g.bind(7, 8, Label::Alpha(0));
+    g.bind(8, 2, Label::Alpha(1));
+    g.put(8, &Hex::from(42));
+    assert_eq!(vec![7, 8], g.prune());
+    assert_eq!(3, g.len());
+    assert_eq!(2, g.kid(1, Label::Alpha(0)).unwrap());
+    assert!(g.prune().is_empty());
+}
+
+#[test]
+fn collects_step_by_step() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    for v in 2..9 {
+        g.add(v);
+    }
+    g.bind(2, 3, Label::Alpha(0));
+    g.put(3, &Hex::from(42));
+    g.add(9);
+    let mut full = g.clone();
+    let expected = full.collect();
+    let mut total = 0;
+    loop {
+        let (reclaimed, done) = g.collect_step(2);
+        assert!(reclaimed <= 2);
+        total += reclaimed;
+        if done {
+            break;
+        }
+    }
+    assert_eq!(expected.len(), total);
+    assert_eq!(full.keys(), g.keys());
+}
+
+#[test]
+fn preserves_unread_data() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(5);
+    g.put(5, &Hex::from(42));
+    g.add(6);
+    g.add_in_branch(7, 3).unwrap();
+    g.bind(5, 7, Label::Alpha(0));
+    g.add(8);
+    let mut pruned = g.clone();
+    assert_eq!(vec![6, 8], g.collect_preserving_data());
+    assert_eq!(42, g.data(5).unwrap().to_i64().unwrap());
+    assert_eq!(vec![5, 6, 7, 8], pruned.prune());
+}
+
+#[test]
+fn reports_complicated_collection() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.add(2);
+    g.bind(1, 2, Label::Alpha(0));
+    g.add(3);
+    g.add(4);
+    g.bind(3, 4, Label::Alpha(0));
+    g.put(4, &Hex::from(42));
+    g.add(5);
+    g.bind(3, 5, Label::Alpha(1));
+    g.add(6);
+    g.add(7);
+    g.bind(6, 7, Label::Alpha(0));
+    let expected = g.clone().collect();
+    let report = g.collect_report();
+    assert_eq!(expected, report.reclaimed);
+    assert_eq!(vec![6, 7], report.reclaimed);
+    assert_eq!(8, report.scanned);
+    assert_eq!(3, report.connected);
+    assert_eq!(3, report.busy);
+}
+
+#[test]
+fn collects_automatically() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    for v in 5..8 {
+        g.add(v);
+        g.bind(1, v, Label::Alpha(v));
+    }
+    g.set_auto_collect(3);
+    g.unbind(1, Label::Alpha(5));
+    g.unbind(1, Label::Alpha(6));
+    assert_eq!(5, g.len());
+    g.unbind(1, Label::Alpha(7));
+    assert_eq!(2, g.len());
+    g.set_auto_collect(0);
+    for v in 10..20 {
+        g.add(v);
+    }
+    assert_eq!(12, g.len());
+    assert!(g.check_invariants().is_ok());
+}
+
+#[test]
+fn collects_nothing_when_all_connected() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    for v in 1..10 {
+        g.add(v);
+        g.bind(v - 1, v, Label::Alpha(0));
+    }
+    g.put(5, &Hex::from(42));
+    let report = g.collect_report();
+    assert_eq!(10, report.scanned);
+    assert_eq!(10, report.connected);
+    assert_eq!(0, report.busy);
+    assert!(report.reclaimed.is_empty());
+    assert_eq!(10, g.len());
+}
+
+#[cfg(feature = "trace")]
+#[test]
+fn skips_phases_when_all_connected() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    let lines = crate::capture_logs(|| {
+        g.collect();
+    });
+    assert!(
+        lines
+            .iter()
+            .any(|l| l.contains("#collect_from: all 2 vertices connected")),
+        "{lines:?}"
+    );
+    g.add(2);
+    let lines = crate::capture_logs(|| {
+        g.collect();
+    });
+    assert!(
+        lines.iter().any(|l| l.contains("1 vertices reclaimed")),
+        "{lines:?}"
+    );
+}
+
+#[test]
+fn collects_beyond_default_branches() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    for i in 0..40 {
+        let v = i * 2 + 1;
+        g.add(v);
+        g.add(v + 1);
+        g.bind(v, v + 1, Label::Alpha(0));
+        g.put(v + 1, &Hex::from(42));
+    }
+    assert!(g.branches.capacity() > 40);
+    assert!(g.collect().is_empty());
+    assert_eq!(81, g.len());
+    for i in 0..20 {
+        let v = i * 2 + 1;
+        assert_eq!(42, g.data(v + 1).unwrap().to_i64().unwrap());
+    }
+    assert_eq!(41, g.len());
+    assert!(g.collect().is_empty());
+    g.check_invariants().unwrap();
+}
+
+#[test]
+fn keeps_graph_consistent_with_auto_collect() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.set_auto_collect(1);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.add(2);
+    g.bind(1, 2, Label::Alpha(0));
+    assert!(g.check_invariants().is_ok());
+    assert_eq!(3, g.len());
+    g.add(3);
+    g.bind(0, 3, Label::Alpha(1));
+    g.unbind(0, Label::Alpha(1));
+    assert!(g.check_invariants().is_ok());
+    assert_eq!(3, g.len());
+}
+
+#[test]
+fn collects_step_by_step_in_bounded_work() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    for v in 1..10 {
+        g.add(v);
+        g.bind(v - 1, v, Label::Alpha(0));
+    }
+    g.add(100);
+    assert_eq!((0, false), g.collect_step(1));
+    assert_eq!(2, g.sweep.as_ref().unwrap().marked.len());
+    let mut steps = 1;
+    while !g.collect_step(10).1 {
+        steps += 1;
+    }
+    assert!(steps > 20);
+    assert_eq!(10, g.len());
+    assert!(g.sweep.is_none());
+}
+
+#[test]
+fn keeps_vertices_bound_during_collection() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.add(5);
+    assert_eq!((0, false), g.collect_step(3));
+    g.add(2);
+    g.bind(1, 2, Label::Alpha(0));
+    g.unbind(0, Label::Alpha(0));
+    g.bind(0, 2, Label::Alpha(1));
+    while !g.collect_step(3).1 {}
+    assert!(g.check_invariants().is_ok());
+    assert_eq!(Some(2), g.kid(0, Label::Alpha(1)));
+    assert!(g.keys().contains(&2));
+    assert!(!g.keys().contains(&5));
+}
+
+#[test]
+fn collects_many_bound_vertices_at_once() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    for v in 2..6 {
+        g.add(v);
+        g.bind(v, 1, Label::Greek('ρ'));
+        g.bind(v, v - 1, Label::Alpha(0));
+    }
+    assert_eq!(vec![2, 3, 4, 5], g.collect());
+    assert_eq!(2, g.len());
+    assert_eq!(Some(1), g.kid(0, Label::Alpha(0)));
+    assert!(g.check_invariants().is_ok());
+}
@@ -18,8 +18,8 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::{Hex, HEX_SIZE};
-use anyhow::{Context, Result};
+use crate::{Hex, HexReader, HexWriter, HEX_SIZE};
+use anyhow::{anyhow, Context, Result};
 use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
@@ -37,6 +37,20 @@ impl PartialEq for Hex {
 
 impl Eq for Hex {}
 
+impl Default for Hex {
+    /// Make an empty [`Hex`], the same as [`Hex::empty`].
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// assert!(Hex::default().is_empty());
+    /// ```
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
 impl Display for Hex {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_str(self.print().as_str())
@@ -98,7 +112,7 @@ impl Hex {
     /// assert_eq!(8, d.len());
     /// ```
     #[must_use]
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         match self {
             Self::Vector(x) => x.len(),
             Self::Bytes(_, size) => *size,
@@ -174,10 +188,38 @@ impl Hex {
     /// assert_eq!(true, d.is_empty());
     /// ```
     #[must_use]
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
+    /// Are the bytes kept inline, without a heap allocation?
+    ///
+    /// Up to eight bytes are kept inline, while longer data goes
+    /// to a vector on the heap. For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// assert!(Hex::from_slice(&[0xCA, 0xFE]).is_inline());
+    /// assert!(!Hex::from_str_bytes("Hello, world!").is_inline());
+    /// ```
+    #[must_use]
+    pub const fn is_inline(&self) -> bool {
+        matches!(self, Self::Bytes(_, _))
+    }
+
+    /// Are the bytes kept in a vector on the heap?
+    ///
+    /// It is the opposite of [`Hex::is_inline`]. For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// assert!(Hex::from_str_bytes("Hello, world!").is_heap());
+    /// ```
+    #[must_use]
+    pub const fn is_heap(&self) -> bool {
+        matches!(self, Self::Vector(_))
+    }
+
     /// Turn it into `bool`.
     ///
     /// For example:
@@ -286,20 +328,63 @@ impl Hex {
         if self.bytes().is_empty() {
             "--".to_string()
         } else {
-            self.bytes()
-                .iter()
-                .map(|b| format!("{b:02X}"))
-                .collect::<Vec<String>>()
-                .join("-")
+            self.print_with("-", true)
         }
     }
 
+    /// Print the bytes with the separator provided, in upper or lower case.
+    ///
+    /// For example, this is how a MAC address is printed:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// let d = Hex::from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+    /// assert_eq!("de:ad:be:ef", d.print_with(":", false));
+    /// assert_eq!("DEADBEEF", d.print_with("", true));
+    /// ```
+    ///
+    /// Unlike [`Hex::print`], an empty `Hex` is printed as an empty string.
+    #[must_use]
+    pub fn print_with(&self, sep: &str, upper: bool) -> String {
+        self.bytes()
+            .iter()
+            .map(|b| {
+                if upper {
+                    format!("{b:02X}")
+                } else {
+                    format!("{b:02x}")
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(sep)
+    }
+
     /// Turn it into a vector of bytes (making a clone).
     #[must_use]
     pub fn to_vec(&self) -> Vec<u8> {
         self.bytes().to_vec()
     }
 
+    /// Turn it into a vector of bytes, without making a clone,
+    /// if the data is already in the heap.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// let d = Hex::from_str_bytes("Hello, world!");
+    /// assert_eq!("Hello, world!".as_bytes(), d.into_vec().as_slice());
+    /// ```
+    ///
+    /// Short data stays inline, and is copied into a new vector.
+    #[must_use]
+    pub fn into_vec(self) -> Vec<u8> {
+        match self {
+            Self::Vector(v) => v,
+            Self::Bytes(b, len) => b[..len].to_vec(),
+        }
+    }
+
     /// Take one byte.
     ///
     /// For example:
@@ -315,6 +400,110 @@ impl Hex {
         self.bytes()[pos]
     }
 
+    /// Take one bit.
+    ///
+    /// Bits are counted from the most significant bit of the first byte,
+    /// so bit zero is the highest bit of byte zero, bit eight is the highest
+    /// bit of byte one, and so on. For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// let d = Hex::from_slice(&[0x80, 0x01]);
+    /// assert!(d.bit_at(0));
+    /// assert!(!d.bit_at(1));
+    /// assert!(d.bit_at(15));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `i` is beyond the number of bits in it, it will panic.
+    #[must_use]
+    pub fn bit_at(&self, i: usize) -> bool {
+        assert!(
+            i < self.len() * 8,
+            "Can't read bit no.{i}, there are just {} bits",
+            self.len() * 8
+        );
+        self.bytes()[i / 8] & (0x80 >> (i % 8)) != 0
+    }
+
+    /// Set or clear one bit.
+    ///
+    /// Bits are counted the same way as in [`Hex::bit_at`]. For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// let mut d = Hex::from_slice(&[0x00, 0x00]);
+    /// d.set_bit(7, true);
+    /// assert_eq!("01-00", d.print());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `i` is beyond the number of bits in it, it will panic.
+    pub fn set_bit(&mut self, i: usize, v: bool) {
+        assert!(
+            i < self.len() * 8,
+            "Can't set bit no.{i}, there are just {} bits",
+            self.len() * 8
+        );
+        let byte = match self {
+            Self::Vector(x) => &mut x[i / 8],
+            Self::Bytes(array, _) => &mut array[i / 8],
+        };
+        let mask = 0x80 >> (i % 8);
+        if v {
+            *byte |= mask;
+        } else {
+            *byte &= !mask;
+        }
+    }
+
+    /// Count, how many bits are set.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// let d = Hex::from_slice(&[0xF0, 0x01]);
+    /// assert_eq!(5, d.popcount());
+    /// ```
+    #[must_use]
+    pub fn popcount(&self) -> u32 {
+        self.bytes().iter().map(|b| b.count_ones()).sum()
+    }
+
+    /// Add one to the bytes, treating them as a big-endian unsigned
+    /// integer, and return a new `Hex` of the same length.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// use std::str::FromStr;
+    /// let d = Hex::from_str("00-FF").unwrap();
+    /// assert_eq!("01-00", d.increment().unwrap().print());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If all bits are set already, or if the `Hex` is empty, there is
+    /// no room for the result and an error will be returned.
+    pub fn increment(&self) -> Result<Self> {
+        let mut bytes = self.to_vec();
+        for b in bytes.iter_mut().rev() {
+            let (sum, carry) = b.overflowing_add(1);
+            *b = sum;
+            if !carry {
+                return Ok(Self::from_vec(bytes));
+            }
+        }
+        Err(anyhow!(
+            "Can't increment {self}, the result doesn't fit into {} bytes",
+            self.len()
+        ))
+    }
+
     /// Skip a few bytes at the beginning and return the rest
     /// as a new instance of `Hex`.
     ///
@@ -330,6 +519,83 @@ impl Hex {
         Self::from_vec(self.bytes()[skip..].to_vec())
     }
 
+    /// Does it start with these bytes?
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// let d = Hex::from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+    /// assert!(d.starts_with(&[0xDE, 0xAD]));
+    /// assert!(d.starts_with(&[]));
+    /// ```
+    #[must_use]
+    pub fn starts_with(&self, prefix: &[u8]) -> bool {
+        self.bytes().starts_with(prefix)
+    }
+
+    /// Does it end with these bytes?
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// let d = Hex::from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+    /// assert!(d.ends_with(&[0xBE, 0xEF]));
+    /// assert!(d.ends_with(&[]));
+    /// ```
+    #[must_use]
+    pub fn ends_with(&self, suffix: &[u8]) -> bool {
+        self.bytes().ends_with(suffix)
+    }
+
+    /// Get the first byte, if there is any.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// let d = Hex::from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+    /// assert_eq!(Some(0xDE), d.first());
+    /// assert_eq!(None, Hex::empty().first());
+    /// ```
+    #[must_use]
+    pub fn first(&self) -> Option<u8> {
+        self.bytes().first().copied()
+    }
+
+    /// Get the last byte, if there is any.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// let d = Hex::from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+    /// assert_eq!(Some(0xEF), d.last());
+    /// assert_eq!(None, Hex::empty().last());
+    /// ```
+    #[must_use]
+    pub fn last(&self) -> Option<u8> {
+        self.bytes().last().copied()
+    }
+
+    /// Does it contain these bytes anywhere inside?
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// let d = Hex::from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+    /// assert!(d.contains_bytes(&[0xAD, 0xBE]));
+    /// assert!(!d.contains_bytes(&[0xEF, 0xDE]));
+    /// ```
+    ///
+    /// An empty needle is contained in any [`Hex`], even in an empty one.
+    #[must_use]
+    pub fn contains_bytes(&self, needle: &[u8]) -> bool {
+        needle.is_empty() || self.bytes().windows(needle.len()).any(|w| w == needle)
+    }
+
     /// Create a new `Hex`, which is a concatenation of `self` and `h`.
     ///
     /// For example:
@@ -356,7 +622,7 @@ impl Hex {
                     Self::Bytes(bytes, l + h.len())
                 } else {
                     let mut v = Vec::new();
-                    v.extend_from_slice(b);
+                    v.extend_from_slice(&b[..*l]);
                     v.extend_from_slice(h.bytes());
                     Self::Vector(v)
                 }
@@ -440,15 +706,152 @@ impl FromStr for Hex {
     /// assert_eq!(Hex::empty(), d2);
     /// ```
     ///
+    /// Spaces may separate the bytes instead of dashes, and the
+    /// string may start with `0x` or `0X`:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// use std::str::FromStr;
+    /// assert_eq!("DE-AD", Hex::from_str("0xDEAD").unwrap().print());
+    /// assert_eq!("DE-AD", Hex::from_str("DE AD").unwrap().print());
+    /// ```
+    ///
     /// # Errors
     ///
     /// If it's impossible to convert from a String, an error will be returned.
     fn from_str(hex: &str) -> std::result::Result<Self, Self::Err> {
-        let s = hex.replace('-', "");
+        let trimmed = hex.trim_start();
+        let body = trimmed
+            .strip_prefix("0x")
+            .or_else(|| trimmed.strip_prefix("0X"))
+            .unwrap_or(trimmed);
+        let s: String = body
+            .chars()
+            .filter(|c| *c != '-' && !c.is_whitespace())
+            .collect();
         Ok(Self::from_vec(hex::decode(s)?))
     }
 }
 
+impl HexWriter {
+    /// Make a new empty writer.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { bytes: vec![] }
+    }
+
+    /// Append an `i64`, as eight bytes.
+    #[must_use]
+    pub fn push_i64(mut self, d: i64) -> Self {
+        self.bytes.extend_from_slice(&d.to_be_bytes());
+        self
+    }
+
+    /// Append an `f64`, as eight bytes.
+    #[must_use]
+    pub fn push_f64(mut self, d: f64) -> Self {
+        self.bytes.extend_from_slice(&d.to_be_bytes());
+        self
+    }
+
+    /// Append a single byte.
+    #[must_use]
+    pub fn push_u8(mut self, d: u8) -> Self {
+        self.bytes.push(d);
+        self
+    }
+
+    /// Append a string, as its length in eight bytes followed by
+    /// its UTF-8 bytes.
+    #[must_use]
+    pub fn push_str(mut self, d: &str) -> Self {
+        self.bytes
+            .extend_from_slice(&(d.len() as u64).to_be_bytes());
+        self.bytes.extend_from_slice(d.as_bytes());
+        self
+    }
+
+    /// Make a [`Hex`] from everything pushed so far.
+    #[must_use]
+    pub fn finish(self) -> Hex {
+        Hex::from_vec(self.bytes)
+    }
+}
+
+impl<'a> HexReader<'a> {
+    /// Make a new reader, starting at the first byte of the [`Hex`].
+    #[must_use]
+    pub const fn new(hex: &'a Hex) -> Self {
+        Self { hex, pos: 0 }
+    }
+
+    /// How many bytes are not read yet.
+    #[must_use]
+    pub const fn remaining(&self) -> usize {
+        self.hex.len() - self.pos
+    }
+
+    /// Read the next `i64`.
+    ///
+    /// # Errors
+    ///
+    /// If there are less than eight bytes left, an error will be returned.
+    pub fn read_i64(&mut self) -> Result<i64> {
+        Ok(i64::from_be_bytes(self.array()?))
+    }
+
+    /// Read the next `f64`.
+    ///
+    /// # Errors
+    ///
+    /// If there are less than eight bytes left, an error will be returned.
+    pub fn read_f64(&mut self) -> Result<f64> {
+        Ok(f64::from_be_bytes(self.array()?))
+    }
+
+    /// Read the next byte.
+    ///
+    /// # Errors
+    ///
+    /// If there are no bytes left, an error will be returned.
+    pub fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Read the next string, written by [`HexWriter::push_str`].
+    ///
+    /// # Errors
+    ///
+    /// If there are not enough bytes left or the string is not
+    /// valid UTF-8, an error will be returned.
+    pub fn read_str(&mut self) -> Result<String> {
+        let len = usize::try_from(u64::from_be_bytes(self.array()?))?;
+        let bytes = self.take(len)?;
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    /// Read the next eight bytes.
+    fn array(&mut self) -> Result<[u8; 8]> {
+        let mut a = [0; 8];
+        a.copy_from_slice(self.take(8)?);
+        Ok(a)
+    }
+
+    /// Read the next `len` bytes and move forward.
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.remaining() {
+            return Err(anyhow!(
+                "Can't read {len} bytes at position {}, only {} left",
+                self.pos,
+                self.remaining()
+            ));
+        }
+        let bytes = &self.hex.bytes()[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+}
+
 #[test]
 fn simple_int() {
     let i = 42;
@@ -610,7 +1013,7 @@ fn concatenates_from_hex_vec() {
     let b = Hex::from_slice(b"as_bytesss");
     let c = Hex::from_vec(vec![0x12, 0xAD]);
     let res = a.concat(&b).concat(&c);
-    assert_eq!(20, res.len());
+    assert_eq!(14, res.len());
 }
 
 #[test]
@@ -621,3 +1024,160 @@ fn concatenates_from_hex_str() {
     let res = a.concat(&b).concat(&c);
     assert_eq!(24, res.len());
 }
+
+#[test]
+fn concatenates_short_into_long() {
+    let a = Hex::from_str_bytes("hello, ");
+    let b = Hex::from_str_bytes("world!");
+    assert_eq!("hello, world!", a.concat(&b).to_utf8().unwrap());
+}
+
+#[test]
+fn sets_and_reads_bits() {
+    let mut d = Hex::from_vec(vec![0x00; 10]);
+    d.set_bit(0, true);
+    d.set_bit(9, true);
+    d.set_bit(79, true);
+    assert!(d.bit_at(0));
+    assert!(!d.bit_at(1));
+    assert!(d.bit_at(9));
+    assert!(d.bit_at(79));
+    assert_eq!(0x40, d.byte_at(1));
+    d.set_bit(0, false);
+    assert!(!d.bit_at(0));
+    let mut s = Hex::from_slice(&[0xFF]);
+    s.set_bit(3, false);
+    assert_eq!("EF", s.print());
+}
+
+#[test]
+fn counts_bits() {
+    let d = Hex::from_str("DE-AD-BE-EF").unwrap();
+    assert_eq!(24, d.popcount());
+    assert_eq!(0, Hex::empty().popcount());
+}
+
+#[test]
+#[should_panic(expected = "Can't read bit no.16")]
+fn reads_bit_out_of_bounds() {
+    let d = Hex::from_slice(&[0x01, 0x02]);
+    let _ = d.bit_at(16);
+}
+
+#[test]
+fn makes_default() {
+    #[derive(Default)]
+    struct Payload {
+        d: Hex,
+    }
+    assert_eq!(Hex::empty(), Hex::default());
+    assert!(Payload::default().d.is_empty());
+}
+
+#[test]
+fn matches_bytes() {
+    let d = Hex::from_str_bytes("Hello, world!");
+    assert!(d.starts_with(b"Hello"));
+    assert!(!d.starts_with(b"world"));
+    assert!(d.ends_with(b"world!"));
+    assert!(!d.ends_with(b"Hello"));
+    assert!(d.contains_bytes(b", w"));
+    assert!(!d.contains_bytes(b"Hello, world!!"));
+    assert!(d.starts_with(b""));
+    assert!(d.ends_with(b""));
+    assert!(d.contains_bytes(b""));
+    assert!(Hex::empty().starts_with(b""));
+    assert!(!Hex::empty().contains_bytes(b"a"));
+}
+
+#[test]
+fn takes_first_and_last_bytes() {
+    let d = Hex::from_str_bytes("Hello, world!");
+    assert_eq!(Some(b'H'), d.first());
+    assert_eq!(Some(b'!'), d.last());
+    let one = Hex::from_slice(&[0x2A]);
+    assert_eq!(one.first(), one.last());
+    assert_eq!(None, Hex::empty().first());
+    assert_eq!(None, Hex::empty().last());
+}
+
+#[test]
+fn parses_prefixed_and_spaced_forms() {
+    assert_eq!("DE-AD", Hex::from_str("0xDEAD").unwrap().print());
+    assert_eq!("DE-AD", Hex::from_str("0XDEAD").unwrap().print());
+    assert_eq!("DE-AD-BE-EF", Hex::from_str("DE AD BE EF").unwrap().print());
+    assert_eq!("DE-AD", Hex::from_str("0x DE-AD").unwrap().print());
+    assert_eq!(Hex::empty(), Hex::from_str("0x").unwrap());
+    assert!(Hex::from_str("DE 0x AD").is_err());
+}
+
+#[test]
+fn moves_bytes_out() {
+    let bytes = b"Hello, world!".to_vec();
+    let ptr = bytes.as_ptr();
+    let d = Hex::from_vec(bytes);
+    let v = d.into_vec();
+    assert_eq!(b"Hello, world!".to_vec(), v);
+    assert_eq!(ptr, v.as_ptr());
+    assert_eq!(vec![1, 2], Hex::from_slice(&[1, 2]).into_vec());
+}
+
+#[test]
+fn increments_counters() {
+    let d = Hex::from_str("00-FF").unwrap();
+    assert_eq!("01-00", d.increment().unwrap().print());
+    assert_eq!(Hex::from(42), Hex::from(41).increment().unwrap());
+    let big = Hex::from_str("00-FF-FF-FF-FF-FF-FF-FF-FF-FF").unwrap();
+    assert_eq!(
+        "01-00-00-00-00-00-00-00-00-00",
+        big.increment().unwrap().print()
+    );
+    assert!(Hex::from_str("FF-FF").unwrap().increment().is_err());
+    assert!(Hex::empty().increment().is_err());
+}
+
+#[test]
+fn packs_typed_values() {
+    let d = HexWriter::new()
+        .push_i64(-7)
+        .push_str("привет")
+        .push_f64(3.5)
+        .push_u8(1)
+        .finish();
+    let mut r = HexReader::new(&d);
+    assert_eq!(-7, r.read_i64().unwrap());
+    assert_eq!("привет", r.read_str().unwrap());
+    assert!((r.read_f64().unwrap() - 3.5).abs() < f64::EPSILON);
+    assert_eq!(1, r.read_u8().unwrap());
+    assert_eq!(0, r.remaining());
+    assert!(r.read_u8().is_err());
+}
+
+#[test]
+fn fails_on_short_string() {
+    let d = HexWriter::new().push_str("hello").finish();
+    let short = Hex::from_slice(&d.bytes()[..10]);
+    assert!(HexReader::new(&short).read_str().is_err());
+}
+
+#[test]
+fn prints_with_separator() {
+    let d = Hex::from_slice(&[0x0A, 0xFF, 0x10]);
+    assert_eq!("0a:ff:10", d.print_with(":", false));
+    assert_eq!("0A FF 10", d.print_with(" ", true));
+    assert_eq!(d.print(), d.print_with("-", true));
+    assert_eq!("", Hex::empty().print_with(":", false));
+    assert_eq!("--", Hex::empty().print());
+}
+
+#[test]
+fn tells_inline_from_heap() {
+    let short = Hex::from_vec(vec![0xCA, 0xFE]);
+    assert!(short.is_inline());
+    assert!(!short.is_heap());
+    let long = Hex::from_slice(&[0x2A; 30]);
+    assert!(long.is_heap());
+    assert!(!long.is_inline());
+    assert!(Hex::from_slice(&[0x2A; HEX_SIZE]).is_inline());
+    assert!(Hex::from_vec(vec![0x2A; HEX_SIZE + 1]).is_heap());
+}
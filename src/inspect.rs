@@ -21,6 +21,7 @@
 use crate::Sodg;
 use anyhow::{Context, Result};
 use itertools::Itertools;
+use serde_json::{json, Map, Value};
 use std::collections::HashSet;
 
 impl<const N: usize> Sodg<N> {
@@ -41,6 +42,64 @@ impl<const N: usize> Sodg<N> {
         ))
     }
 
+    /// Print the tree of sub-objects and edges of a vertex as JSON.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use sodg::{Hex, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// g.bind(1, 0, Label::Greek('ρ'));
+    /// g.put(1, &Hex::from_slice(&[0xDE, 0xAD]));
+    /// assert_eq!(
+    ///     json!({"id": 0, "kids": {"α0": {
+    ///         "id": 1, "data": "DE-AD", "kids": {"ρ": {"ref": 0}}
+    ///     }}}),
+    ///     g.inspect_json(0).unwrap(),
+    /// );
+    /// ```
+    ///
+    /// Every vertex is printed only once, just like in [`Sodg::inspect`].
+    /// When it is met again, it is printed as `{"ref": id}`.
+    ///
+    /// # Errors
+    ///
+    /// If it's impossible to inspect, an error will be returned.
+    pub fn inspect_json(&self, v: usize) -> Result<Value> {
+        let mut seen = HashSet::new();
+        self.inspect_json_v(v, &mut seen)
+    }
+
+    fn inspect_json_v(&self, v: usize, seen: &mut HashSet<usize>) -> Result<Value> {
+        seen.insert(v);
+        let vtx = self
+            .vertices
+            .get(v)
+            .with_context(|| format!("Can't find ν{v}"))?;
+        let mut kids = Map::new();
+        for e in vtx.edges.iter().sorted() {
+            let kid = if seen.contains(e.1) {
+                json!({ "ref": e.1 })
+            } else {
+                self.inspect_json_v(*e.1, seen)?
+            };
+            kids.insert(e.0.to_string(), kid);
+        }
+        let mut obj = Map::new();
+        obj.insert("id".to_string(), json!(v));
+        if let Some(d) = self.data_ref(v) {
+            obj.insert("data".to_string(), json!(d.print()));
+        }
+        if !kids.is_empty() {
+            obj.insert("kids".to_string(), Value::Object(kids));
+        }
+        Ok(Value::Object(obj))
+    }
+
     fn inspect_v(&self, v: usize, seen: &mut HashSet<usize>) -> Result<Vec<String>> {
         seen.insert(v);
         let mut lines = vec![];
@@ -91,3 +150,35 @@ fn inspects_simple_object() {
     g.bind(0, 1, Label::Alpha(0));
     assert_ne!(String::new(), txt);
 }
+
+#[test]
+fn inspects_as_json() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.add(2);
+    g.bind(0, 1, Label::Alpha(0));
+    g.bind(0, 2, Label::Alpha(1));
+    g.bind(1, 2, Label::Greek('φ'));
+    g.bind(2, 0, Label::Greek('ρ'));
+    g.put(2, &Hex::from(42));
+    assert_eq!(
+        json!({
+            "id": 0,
+            "kids": {
+                "α0": {
+                    "id": 1,
+                    "kids": {
+                        "φ": {
+                            "id": 2,
+                            "data": "00-00-00-00-00-00-00-2A",
+                            "kids": { "ρ": { "ref": 0 } }
+                        }
+                    }
+                },
+                "α1": { "ref": 2 }
+            }
+        }),
+        g.inspect_json(0).unwrap()
+    );
+}
@@ -0,0 +1,242 @@
+// Copyright (c) 2022-2025 Objectionary.com
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included
+// in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{Persistence, Sodg, BRANCH_NONE, BRANCH_STATIC};
+use anyhow::{anyhow, Result};
+
+impl<const N: usize> Sodg<N> {
+    /// Check the internal consistency of the graph.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// g.put(1, &Hex::from(42));
+    /// g.check_invariants().unwrap();
+    /// ```
+    ///
+    /// These invariants are checked: 1) no edge of an alive vertex leads
+    /// to an absent vertex, 2) members of each branch stay in this branch,
+    /// according to their `branch` fields, and vice versa, 3) the number
+    /// of stores in each branch equals the number of vertices with
+    /// stored data in it.
+    ///
+    /// # Errors
+    ///
+    /// If any of the invariants is broken, an `Err` will be returned,
+    /// explaining which one.
+    ///
+    /// # Panics
+    ///
+    /// May panic if branches are out of their capacity (should never happen, though).
+    pub fn check_invariants(&self) -> Result<()> {
+        let mut stored = vec![0; self.branches.capacity()];
+        for (v, vtx) in self.vertices.iter() {
+            if vtx.branch == BRANCH_NONE {
+                continue;
+            }
+            for (a, to) in &vtx.edges {
+                if self
+                    .vertices
+                    .get(*to)
+                    .is_none_or(|t| t.branch == BRANCH_NONE)
+                {
+                    return Err(anyhow!("The edge ν{v}.{a} leads to ν{to}, which is absent"));
+                }
+            }
+            if vtx.branch > BRANCH_STATIC
+                && !self
+                    .branches
+                    .get(vtx.branch)
+                    .unwrap()
+                    .into_iter()
+                    .any(|m| m == v)
+            {
+                return Err(anyhow!(
+                    "The vertex ν{v} is in branch no.{}, but is not its member",
+                    vtx.branch
+                ));
+            }
+            if vtx.persistence == Persistence::Stored {
+                stored[vtx.branch] += 1;
+            }
+        }
+        for b in BRANCH_STATIC + 1..self.branches.capacity() {
+            for m in self.branches.get(b).unwrap().into_iter() {
+                let branch = self.vertices.get(m).map_or(BRANCH_NONE, |vtx| vtx.branch);
+                if branch != b {
+                    return Err(anyhow!(
+                        "The vertex ν{m} is a member of branch no.{b}, but is in branch no.{branch}"
+                    ));
+                }
+            }
+        }
+        for (b, total) in stored.iter().enumerate().skip(BRANCH_STATIC) {
+            let counted = *self.stores.get(b).unwrap();
+            if counted != *total {
+                return Err(anyhow!(
+                    "There are {counted} stores in branch no.{b}, while {total} vertices have data"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Count the stores of all branches together.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// g.put(1, &Hex::from(42));
+    /// assert_eq!(1, g.store_total());
+    /// g.data(1);
+    /// assert_eq!(0, g.store_total());
+    /// ```
+    ///
+    /// In a correct graph it is always equal to [`Sodg::stored_vertices`].
+    #[must_use]
+    pub fn store_total(&self) -> usize {
+        self.stores.values().sum()
+    }
+
+    /// Count the alive vertices with stored data, which is not taken yet.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.put(0, &Hex::from(42));
+    /// assert_eq!(1, g.stored_vertices());
+    /// ```
+    #[must_use]
+    pub fn stored_vertices(&self) -> usize {
+        self.vertices
+            .values()
+            .filter(|vtx| vtx.branch != BRANCH_NONE && vtx.persistence == Persistence::Stored)
+            .count()
+    }
+
+    /// Turn on or off the strict mode, where [`Sodg::check_invariants`]
+    /// is called after every [`Sodg::add`], [`Sodg::bind`], [`Sodg::put`],
+    /// and [`Sodg::data`].
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.strict(true);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// ```
+    ///
+    /// If any invariant is broken in the strict mode, the operation
+    /// panics. This is slow and is meant for debugging only.
+    pub const fn strict(&mut self, on: bool) {
+        self.strict = on;
+    }
+
+    /// Check the invariants, if the strict mode is on.
+    pub(crate) fn verify(&self) {
+        if self.strict {
+            if let Err(e) = self.check_invariants() {
+                panic!("{e}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::{Hex, Label};
+
+#[test]
+fn passes_well_formed_graph() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.strict(true);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.add(2);
+    g.bind(1, 2, Label::Alpha(0));
+    g.put(2, &Hex::from(42));
+    g.put(2, &Hex::from(7));
+    g.add(3);
+    g.bind(0, 3, Label::Alpha(1));
+    g.put(3, &Hex::from(1));
+    g.data(2);
+    assert!(g.check_invariants().is_ok());
+}
+
+#[test]
+fn reports_corrupted_store_counter() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.put(1, &Hex::from(42));
+    *g.stores.get_mut(2).unwrap() += 1;
+    let err = g.check_invariants().unwrap_err().to_string();
+    assert!(err.contains("2 stores in branch no.2"), "{err}");
+}
+
+#[test]
+#[should_panic(expected = "stores in branch")]
+fn panics_in_strict_mode() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.strict(true);
+    g.add(0);
+    *g.stores.get_mut(1).unwrap() += 1;
+    g.add(1);
+}
+
+#[test]
+fn counts_stores_consistently() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.put(0, &Hex::from(1));
+    assert_eq!(g.store_total(), g.stored_vertices());
+    for v in 1..6 {
+        g.add(v);
+        g.bind(0, v, Label::Alpha(v));
+        g.put(v, &Hex::from(42));
+        assert_eq!(g.store_total(), g.stored_vertices());
+        g.put(v, &Hex::from(7));
+        assert_eq!(g.store_total(), g.stored_vertices());
+    }
+    g.data(2);
+    assert_eq!(g.store_total(), g.stored_vertices());
+    g.data(4);
+    assert_eq!(g.store_total(), g.stored_vertices());
+    g.put(2, &Hex::from(3));
+    assert_eq!(g.store_total(), g.stored_vertices());
+    assert_eq!(5, g.stored_vertices());
+}
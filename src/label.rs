@@ -18,32 +18,210 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::Label;
-use anyhow::anyhow;
+use crate::{Label, LabelTable};
+use anyhow::{anyhow, Result};
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
+impl Label {
+    /// The letters of [`Label::Greek`] edges that are made by the runtime
+    /// for its own needs, rather than by a user, see [`Label::is_system`].
+    ///
+    /// Both `φ` and its mathematical form `𝜑` are here, since both
+    /// are used in EO programs.
+    pub const SYSTEM: [char; 5] = ['ρ', 'σ', 'φ', '𝜑', 'π'];
+
+    /// Is it a system edge, one of [`Label::SYSTEM`]?
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Label;
+    /// assert!(Label::Greek('ρ').is_system());
+    /// assert!(!Label::Greek('Δ').is_system());
+    /// assert!(!Label::Alpha(0).is_system());
+    /// ```
+    #[must_use]
+    pub fn is_system(&self) -> bool {
+        matches!(self, Self::Greek(c) if Self::SYSTEM.contains(c))
+    }
+}
+
+impl LabelTable {
+    /// Make an empty table.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a name and return its ID.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::LabelTable;
+    /// let mut t = LabelTable::new();
+    /// let id = t.intern("hello");
+    /// assert_eq!(id, t.intern("hello"));
+    /// assert_eq!(Some("hello".to_string()), t.resolve(id));
+    /// ```
+    ///
+    /// The same name always gets the same ID, while the IDs
+    /// are given out in the order names are registered, starting from zero.
+    ///
+    /// # Panics
+    ///
+    /// If there are more than `u32::MAX` names in the table, it will panic.
+    pub fn intern(&mut self, name: &str) -> u32 {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = u32::try_from(self.names.len()).unwrap();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        id
+    }
+
+    /// Find the name of an ID given out by [`LabelTable::intern`].
+    #[must_use]
+    pub fn resolve(&self, id: u32) -> Option<String> {
+        self.names.get(id as usize).cloned()
+    }
+
+    /// Find the ID of a name already registered by [`LabelTable::intern`].
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::LabelTable;
+    /// let mut t = LabelTable::new();
+    /// assert!(t.find("foo").is_none());
+    /// let id = t.intern("foo");
+    /// assert_eq!(Some(id), t.find("foo"));
+    /// ```
+    #[must_use]
+    pub fn find(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    /// How many names are in the table?
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Is it empty?
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+impl From<Vec<String>> for LabelTable {
+    fn from(names: Vec<String>) -> Self {
+        let mut t = Self::new();
+        for name in &names {
+            t.intern(name);
+        }
+        t
+    }
+}
+
+impl From<LabelTable> for Vec<String> {
+    fn from(t: LabelTable) -> Self {
+        t.names
+    }
+}
+
 impl FromStr for Label {
     type Err = anyhow::Error;
 
+    /// Parse a label.
+    ///
+    /// A label in double quotes, like `"a b"`, is always a [`Label::Str`],
+    /// keeping everything between the quotes as is, including spaces.
+    /// Trailing spaces are lost, though, since they pad the label to
+    /// its eight chars. A label with spaces inside is printed back
+    /// in double quotes too, and so is a label which, without them,
+    /// would be parsed as another one, like `"ρ"` or `"α5"`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(if s.starts_with('α') {
-            let tail: String = s.chars().skip(1).collect::<Vec<_>>().into_iter().collect();
-            Self::Alpha(tail.parse::<usize>()?)
-        } else if s.len() == 1 {
-            Self::Greek(s.chars().next().unwrap())
-        } else {
-            let v: Vec<char> = s.chars().collect();
-            let mut a: [char; 8] = [' '; 8];
-            for (i, c) in v.into_iter().enumerate() {
-                if i > 7 {
-                    return Err(anyhow!("Can't parse more than {} chars", a.len()));
-                }
-                a[i] = c;
+        if let Some(inner) = s.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+            return Self::str_of(inner);
+        }
+        Ok(
+            if let Some(id) = s
+                .strip_prefix('@')
+                .filter(|t| !t.is_empty() && t.chars().all(|c| c.is_ascii_digit()))
+            {
+                Self::Id(id.parse::<u32>()?)
+            } else if s.starts_with('α') {
+                let tail: String = s.chars().skip(1).collect::<Vec<_>>().into_iter().collect();
+                Self::Alpha(tail.parse::<usize>()?)
+            } else if s.chars().count() == 1 {
+                Self::Greek(s.chars().next().unwrap())
+            } else {
+                Self::str_of(s)?
+            },
+        )
+    }
+}
+
+impl Label {
+    /// Make the normal form of the label: a [`Label::Str`] that spells
+    /// a [`Label::Greek`] or a [`Label::Alpha`] becomes that label.
+    pub(crate) fn canonical(&self) -> Self {
+        if let Self::Str(a) = self {
+            let plain: String = a.iter().collect::<String>().trim_end().to_string();
+            if let Ok(l @ (Self::Greek(_) | Self::Alpha(_))) = Self::from_str(plain.as_str()) {
+                return l;
+            }
+        }
+        *self
+    }
+
+    /// Make a [`Label::Str`] of these chars, padding it with spaces.
+    fn str_of(s: &str) -> Result<Self> {
+        let mut a: [char; 8] = [' '; 8];
+        for (i, c) in s.chars().enumerate() {
+            if i > 7 {
+                return Err(anyhow!("Can't parse more than {} chars", a.len()));
             }
-            Self::Str(a)
-        })
+            a[i] = c;
+        }
+        Ok(Self::Str(a))
+    }
+}
+
+impl TryFrom<char> for Label {
+    type Error = anyhow::Error;
+
+    /// Make a [`Label::Greek`] from a single char.
+    ///
+    /// The char `α` is not accepted, since [`Label::from_str`] treats it
+    /// as a prefix of [`Label::Alpha`]. Whitespace chars are not accepted
+    /// either, since they can't be printed back.
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        if c == 'α' || c.is_whitespace() {
+            return Err(anyhow!("Can't make a label from '{c}'"));
+        }
+        Ok(Self::Greek(c))
+    }
+}
+
+impl TryFrom<&str> for Label {
+    type Error = anyhow::Error;
+
+    /// Parse a label, just like [`Label::from_str`] does.
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::from_str(s)
+    }
+}
+
+impl From<usize> for Label {
+    /// Make a [`Label::Alpha`] from its index.
+    fn from(i: usize) -> Self {
+        Self::Alpha(i)
     }
 }
 
@@ -58,8 +236,14 @@ impl Debug for Label {
         match *self {
             Self::Greek(c) => f.write_str(format!("{c}").as_str()),
             Self::Alpha(i) => f.write_str(format!("α{i}").as_str()),
+            Self::Id(i) => f.write_str(format!("@{i}").as_str()),
             Self::Str(a) => {
-                f.write_str(a.iter().filter(|c| **c != ' ').collect::<String>().as_str())
+                let plain: String = a.iter().collect::<String>().trim_end().to_string();
+                if plain.contains(' ') || Self::from_str(plain.as_str()).ok() != Some(*self) {
+                    f.write_str(format!("\"{plain}\"").as_str())
+                } else {
+                    f.write_str(plain.as_str())
+                }
             }
         }
     }
@@ -71,7 +255,106 @@ use rstest::rstest;
 #[case("𝜑")]
 #[case("α5")]
 #[case("hello")]
+#[case("@42")]
 fn parses_and_prints(#[case] txt: &str) {
     let l = Label::from_str(txt).unwrap();
     assert_eq!(txt, l.to_string());
 }
+
+#[test]
+fn makes_label_from_char_and_number() {
+    assert_eq!(Label::Alpha(3), Label::from(3usize));
+    assert_eq!(Label::Greek('ρ'), Label::try_from('ρ').unwrap());
+    assert_eq!(Label::from_str("ρ").unwrap(), Label::try_from('ρ').unwrap());
+    assert!(Label::try_from('α').is_err());
+    assert!(Label::try_from(' ').is_err());
+}
+
+#[test]
+fn tells_system_labels() {
+    for c in Label::SYSTEM {
+        assert!(Label::Greek(c).is_system());
+    }
+    assert!(!Label::from_str("foo").unwrap().is_system());
+    assert!(!Label::Id(0).is_system());
+}
+
+#[test]
+fn parses_quoted_labels() {
+    let l = Label::from_str("\"a b\"").unwrap();
+    assert_eq!(Label::Str(['a', ' ', 'b', ' ', ' ', ' ', ' ', ' ']), l);
+    assert_eq!("\"a b\"", l.to_string());
+    assert_eq!(l, Label::from_str(&l.to_string()).unwrap());
+    assert!(matches!(Label::from_str("\"ρ\"").unwrap(), Label::Str(_)));
+    let lead = Label::from_str("\" x\"").unwrap();
+    assert_eq!(lead, Label::from_str(&lead.to_string()).unwrap());
+    assert_eq!(
+        Label::from_str("foo").unwrap(),
+        Label::from_str("\"foo\"").unwrap()
+    );
+    assert_eq!("foo", Label::from_str("\"foo\"").unwrap().to_string());
+    assert!(Label::from_str("\"too long label\"").is_err());
+}
+
+#[rstest]
+#[case("\"ρ\"")]
+#[case("\"α5\"")]
+#[case("\"@42\"")]
+#[case("\"αβ\"")]
+#[case("\"\"ab\"\"")]
+fn quotes_ambiguous_strings(#[case] txt: &str) {
+    let l = Label::from_str(txt).unwrap();
+    assert!(matches!(l, Label::Str(_)));
+    assert_eq!(txt, l.to_string());
+    assert_eq!(l, Label::from_str(&l.to_string()).unwrap());
+    assert_eq!(l, Label::from_str(&format!("{l:?}")).unwrap());
+}
+
+#[test]
+fn interns_names_in_table() {
+    let mut t = LabelTable::new();
+    assert!(t.is_empty());
+    let foo = t.intern("foo");
+    let bar = t.intern("a very long name, longer than eight chars");
+    assert_eq!(foo, t.intern("foo"));
+    assert_ne!(foo, bar);
+    assert_eq!(2, t.len());
+    assert_eq!(Some("foo".to_string()), t.resolve(foo));
+    assert_eq!(
+        Some("a very long name, longer than eight chars".to_string()),
+        t.resolve(bar)
+    );
+    assert_eq!(None, t.resolve(42));
+}
+
+#[test]
+fn serializes_table_as_names() {
+    let mut t = LabelTable::new();
+    let foo = t.intern("foo");
+    let bar = t.intern("bar");
+    let names: Vec<String> = t.clone().into();
+    assert_eq!(vec!["foo".to_string(), "bar".to_string()], names);
+    let bytes = bincode::serialize(&t).unwrap();
+    assert_eq!(bytes, bincode::serialize(&names).unwrap());
+    let after: LabelTable = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(Some(foo), after.find("foo"));
+    assert_eq!(Some(bar), after.find("bar"));
+    assert_eq!(2, after.len());
+}
+
+#[test]
+fn makes_canonical_labels() {
+    assert_eq!(
+        Label::Greek('ρ'),
+        Label::from_str("\"ρ\"").unwrap().canonical()
+    );
+    assert_eq!(
+        Label::Alpha(5),
+        Label::from_str("\"α5\"").unwrap().canonical()
+    );
+    assert_eq!(
+        Label::from_str("foo").unwrap(),
+        Label::from_str("foo").unwrap().canonical()
+    );
+    assert_eq!(Label::Alpha(1), Label::Alpha(1).canonical());
+}
@@ -42,13 +42,23 @@
 #![warn(clippy::all, clippy::pedantic, clippy::nursery, clippy::cargo)]
 #![allow(clippy::multiple_inherent_impl)]
 #![allow(clippy::multiple_crate_versions)]
+#![allow(clippy::non_std_lazy_statics)]
 
+pub mod alerts;
+mod analysis;
+mod binary;
 mod clone;
 mod ctors;
 mod debug;
 mod dot;
+mod events;
+mod find;
+#[cfg(feature = "sync")]
+mod frozen;
+mod gc;
 mod hex;
 mod inspect;
+mod invariants;
 mod label;
 mod merge;
 mod misc;
@@ -57,10 +67,17 @@ mod ops;
 mod script;
 mod serialization;
 mod slice;
+mod symbols;
+mod tx;
 mod xml;
 
+pub use misc::{remap_ids, remap_labeled};
+pub use xml::SODG_DTD;
+
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 
 const HEX_SIZE: usize = 8;
 const MAX_BRANCHES: usize = 16;
@@ -90,12 +107,194 @@ pub enum Hex {
     Bytes([u8; HEX_SIZE], usize),
 }
 
+/// An iterator over IDs of live vertices of a [`Sodg`].
+///
+/// It is made by iterating a reference to the graph, for example:
+///
+/// ```
+/// use sodg::Sodg;
+/// let mut g : Sodg<16> = Sodg::empty(256);
+/// g.add(0);
+/// g.add(42);
+/// let mut ids = vec![];
+/// for v in &g {
+///     ids.push(v);
+/// }
+/// ids.sort_unstable();
+/// assert_eq!(vec![0, 42], ids);
+/// ```
+pub struct VertexIds<'a, const N: usize> {
+    iter: emap::Iter<'a, Vertex<N>>,
+}
+
+/// A builder of a [`Hex`] from a few typed values.
+///
+/// For example:
+///
+/// ```
+/// use sodg::{HexReader, HexWriter};
+/// let d = HexWriter::new().push_i64(42).push_str("hello").finish();
+/// let mut r = HexReader::new(&d);
+/// assert_eq!(42, r.read_i64().unwrap());
+/// assert_eq!("hello", r.read_str().unwrap());
+/// ```
+///
+/// Numbers are written in big-endian order, just like [`Hex::from`] does.
+#[derive(Debug, Default, Clone)]
+pub struct HexWriter {
+    bytes: Vec<u8>,
+}
+
+/// A reader of typed values from a [`Hex`], made by [`HexWriter`].
+pub struct HexReader<'a> {
+    hex: &'a Hex,
+    pos: usize,
+}
+
+/// Data that may be shared by many vertices, see [`Sodg::put_shared`].
+///
+/// It is an [`Rc`](std::rc::Rc) by default, or an [`Arc`](std::sync::Arc)
+/// if the `sync` feature is enabled.
+#[cfg(not(feature = "sync"))]
+pub type SharedHex = std::rc::Rc<Hex>;
+
+/// Data that may be shared by many vertices, see [`Sodg::put_shared`].
+///
+/// It is an [`Rc`](std::rc::Rc) by default, or an [`Arc`](std::sync::Arc)
+/// if the `sync` feature is enabled.
+#[cfg(feature = "sync")]
+pub type SharedHex = std::sync::Arc<Hex>;
+
+/// A check of vertices of a [`Sodg`], attached by [`Sodg::alert_on`].
+///
+/// It returns a list of problems found in the vertices provided.
+pub type Alert<const N: usize> = fn(&Sodg<N>, Vec<usize>) -> Vec<String>;
+
+/// A producer of data for a vertex of a [`Sodg`], registered by [`Sodg::set_lambda`].
+pub type Lambda<const N: usize> = fn(&Sodg<N>, usize) -> Hex;
+
 /// A label on an edge.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Serialize, Deserialize)]
 pub enum Label {
     Greek(char),
     Alpha(usize),
     Str([char; 8]),
+    /// An index in the symbol table of a [`Sodg`], see [`Sodg::intern`].
+    Id(u32),
+}
+
+/// A table of names, each with a stable `u32` ID, made by [`LabelTable::intern`].
+///
+/// Every [`Sodg`] keeps one, filled by [`Sodg::intern`], but the table
+/// may also be used on its own. It is serialized as the list of its names.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(from = "Vec<String>", into = "Vec<String>")]
+pub struct LabelTable {
+    names: Vec<String>,
+    ids: HashMap<String, u32>,
+}
+
+/// A change made to a [`Sodg`], reported to the listener
+/// registered by [`Sodg::on_change`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A vertex was added.
+    Added(usize),
+    /// An edge was made from the first vertex to the second one.
+    Bound(usize, usize, Label),
+    /// An edge with this label was removed from the vertex.
+    Unbound(usize, Label),
+    /// Data was put into a vertex.
+    Put(usize),
+    /// Data of a vertex was taken for the first time.
+    Took(usize),
+    /// These vertices were removed by garbage collection, or together
+    /// with their branch, when its last data was taken.
+    Collected(Vec<usize>),
+}
+
+/// A relay that is used by [`Sodg::find`] when it can't find an attribute.
+///
+/// The finding algorithm asks the relay for the locator to follow
+/// instead of the missing attribute.
+pub trait Relay {
+    /// A method to be called when the searching algorithm
+    /// fails to find the required attribute `a` in vertex `v`.
+    ///
+    /// # Errors
+    ///
+    /// If the relay can't suggest any alternative locator,
+    /// an error must be returned.
+    fn re(&self, v: usize, a: &Label) -> Result<String>;
+}
+
+/// A [`Relay`] that doesn't even try to find anything, but returns an error.
+///
+/// If you don't know what [`Relay`] to use, use [`DeadRelay::default()`].
+#[derive(Default)]
+pub struct DeadRelay {}
+
+/// A [`Relay`] that is made of a lambda function.
+///
+/// The function must accept two arguments: the vertex and the
+/// attribute that is missing. It must return the locator to follow.
+pub struct LambdaRelay {
+    lambda: fn(usize, &Label) -> Result<String>,
+}
+
+/// A [`Sodg`] printable as a DOT graph, made by [`Sodg::dot`].
+pub struct Dot<'a, const N: usize>(&'a Sodg<N>);
+
+/// A [`Sodg`] printable as an XML document, made by [`Sodg::xml`].
+pub struct Xml<'a, const N: usize>(&'a Sodg<N>);
+
+/// An immutable snapshot of a [`Sodg`], made by [`Sodg::freeze`].
+///
+/// It may be shared between threads, since it is made only of plain
+/// vectors and maps, unlike the [`Sodg`] itself. The most frequent
+/// reading methods are available right on it, while all others are
+/// available on the graph made by [`FrozenSodg::thaw`].
+#[cfg(feature = "sync")]
+pub struct FrozenSodg<const N: usize> {
+    /// The slots of the vertices, by their IDs.
+    vertices: Vec<Option<Vertex<N>>>,
+    /// The slots of the branches, by their IDs.
+    branches: Vec<Option<microstack::Stack<usize, MAX_BRANCH_SIZE>>>,
+    /// The slots of the counters of stored data in the branches.
+    stores: Vec<Option<usize>>,
+    next_v: usize,
+    alive: usize,
+    symbols: LabelTable,
+    alerts: Vec<Alert<N>>,
+    lambdas: HashMap<usize, Lambda<N>>,
+    strict: bool,
+    auto_collect: usize,
+}
+
+/// A summary of a garbage collection, made by [`Sodg::collect_report`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CollectReport {
+    /// How many alive vertices were seen.
+    pub scanned: usize,
+    /// How many of them were reachable from the roots.
+    pub connected: usize,
+    /// How many of the unreachable ones were kept, because they were busy.
+    pub busy: usize,
+    /// The IDs of the removed vertices, in ascending order.
+    pub reclaimed: Vec<usize>,
+}
+
+/// A summary of a vertex, given to the predicate of [`Sodg::keys_where`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VertexSummary {
+    /// The branch the vertex is in.
+    pub branch: usize,
+    /// How many edges go out of it.
+    pub degree: usize,
+    /// The length of its data in bytes, if it has any.
+    pub data: Option<usize>,
+    /// Whether its data was already taken.
+    pub taken: bool,
 }
 
 /// A wrapper of a plain text with graph-modifying instructions.
@@ -142,10 +341,60 @@ pub struct Script {
 pub struct Sodg<const N: usize> {
     stores: emap::Map<usize>,
     branches: emap::Map<microstack::Stack<usize, MAX_BRANCH_SIZE>>,
+    #[serde(deserialize_with = "serialization::vertices")]
     vertices: emap::Map<Vertex<N>>,
     /// This is the next ID of a vertex to be returned by the [`Sodg::next_v`] function.
     #[serde(skip_serializing, skip_deserializing)]
     next_v: usize,
+    /// This is the number of live vertices, returned by [`Sodg::len`].
+    #[serde(skip_serializing, skip_deserializing)]
+    alive: usize,
+    /// This is the listener of changes, set by [`Sodg::on_change`].
+    #[serde(skip_serializing, skip_deserializing)]
+    listener: Option<fn(&Event)>,
+    /// This is the cache of [`Sodg::find`], enabled by [`Sodg::enable_find_cache`].
+    #[serde(skip_serializing, skip_deserializing)]
+    finds: Option<RefCell<FindCache>>,
+    /// These are the names of [`Label::Id`] labels, registered by [`Sodg::intern`].
+    /// It is saved by [`Sodg::save`] after the graph, not inside it.
+    #[serde(skip_serializing, skip_deserializing)]
+    symbols: LabelTable,
+    /// These are the alerts, attached by [`Sodg::alert_on`].
+    #[serde(skip_serializing, skip_deserializing)]
+    alerts: Vec<Alert<N>>,
+    /// These are the producers of data, registered by [`Sodg::set_lambda`].
+    #[serde(skip_serializing, skip_deserializing)]
+    lambdas: HashMap<usize, Lambda<N>>,
+    /// This is the strict mode, turned on by [`Sodg::strict`].
+    #[serde(skip_serializing, skip_deserializing)]
+    strict: bool,
+    /// This is how often the garbage is collected, set by [`Sodg::set_auto_collect`].
+    #[serde(skip_serializing, skip_deserializing)]
+    auto_collect: usize,
+    /// This is the number of mutations since the last automatic collection.
+    #[serde(skip_serializing, skip_deserializing)]
+    mutations: usize,
+    /// This is the state of the collection made by [`Sodg::collect_step`].
+    #[serde(skip_serializing, skip_deserializing)]
+    sweep: Option<Sweep>,
+}
+
+/// Resolved locators, mapped from the start vertex and the locator.
+#[derive(Default, Clone)]
+struct FindCache {
+    resolved: HashMap<(usize, String), usize>,
+    hits: usize,
+}
+
+/// The state of garbage collection made step by step.
+#[derive(Default, Clone)]
+struct Sweep {
+    /// Vertices known to be reachable from the root.
+    marked: HashSet<usize>,
+    /// Marked vertices, whose kids are not marked yet.
+    todo: Vec<usize>,
+    /// The next vertex to check, when all reachable ones are marked.
+    cursor: usize,
 }
 
 #[derive(PartialEq, Serialize, Deserialize, Clone)]
@@ -161,8 +410,9 @@ const BRANCH_STATIC: usize = 1;
 #[derive(Serialize, Deserialize, Clone)]
 struct Vertex<const N: usize> {
     branch: usize,
-    data: Hex,
+    data: SharedHex,
     persistence: Persistence,
+    #[serde(deserialize_with = "serialization::edges")]
     edges: micromap::Map<Label, usize, N>,
 }
 
@@ -172,12 +422,53 @@ use simple_logger::SimpleLogger;
 #[cfg(test)]
 use log::LevelFilter;
 
+#[cfg(test)]
+thread_local! {
+    /// Log messages of the current thread, captured by [`capture_logs`].
+    static CAPTURED: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// A logger that prints everything and also captures messages,
+/// when asked by [`capture_logs`].
+#[cfg(test)]
+struct TestLogger(SimpleLogger);
+
+#[cfg(test)]
+impl log::Log for TestLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        CAPTURED.with(|c| {
+            if let Some(lines) = c.borrow_mut().as_mut() {
+                lines.push(record.args().to_string());
+            }
+        });
+        self.0.log(record);
+    }
+
+    fn flush(&self) {
+        self.0.flush();
+    }
+}
+
+/// Run the function and return all log messages it made.
+#[cfg(all(test, feature = "trace"))]
+fn capture_logs(f: impl FnOnce()) -> Vec<String> {
+    CAPTURED.with(|c| *c.borrow_mut() = Some(vec![]));
+    f();
+    CAPTURED.with(|c| c.borrow_mut().take().unwrap())
+}
+
 #[cfg(test)]
 #[ctor::ctor]
 fn init() {
-    SimpleLogger::new()
-        .without_timestamps()
-        .with_level(LevelFilter::Trace)
-        .init()
-        .unwrap();
+    log::set_boxed_logger(Box::new(TestLogger(
+        SimpleLogger::new()
+            .without_timestamps()
+            .with_level(LevelFilter::Trace),
+    )))
+    .unwrap();
+    log::set_max_level(LevelFilter::Trace);
 }
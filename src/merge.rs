@@ -18,54 +18,366 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::{Label, Persistence, Sodg};
+use crate::{Hex, Label, Persistence, SharedHex, Sodg, BRANCH_NONE};
 use anyhow::{anyhow, Result};
 use log::debug;
+#[cfg(feature = "trace")]
+use log::trace;
 use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+/// How many vertices are merged between two progress reports
+/// of [`Sodg::merge`], if the `trace` feature is enabled.
+#[cfg(feature = "trace")]
+const MERGE_PROGRESS: usize = 10;
+
+/// The rules of a merge, followed by [`Sodg::merge_rec`].
+struct Rules<'a> {
+    /// Only the edges of the right graph that match it are followed.
+    follow: &'a dyn Fn(usize, usize, Label) -> bool,
+    /// The labels of the right graph are renamed by it.
+    relabel: &'a dyn Fn(Label) -> Label,
+    /// New vertices stay in the static branch, instead of getting
+    /// into the branches [`Sodg::bind`] decides.
+    fixed: bool,
+    /// All vertices of the right graph must be merged.
+    whole: bool,
+}
+
+impl Default for Rules<'_> {
+    fn default() -> Self {
+        Self {
+            follow: &|_, _, _| true,
+            relabel: &|a| a,
+            fixed: false,
+            whole: true,
+        }
+    }
+}
 
 impl<const N: usize> Sodg<N> {
     /// Merge another graph into the current one.
     ///
-    /// It is expected that both graphs are trees! If they are not, the result is unpredictable.
-    ///
     /// The `right` vertex is mapped to the `left` vertex. The decisions about
-    /// their kids are made recursively.
+    /// their kids are made recursively. The graphs don't have to be trees:
+    /// a vertex reachable through many paths, or through a loop, is merged
+    /// only once. However, all vertices of the right graph must be reachable
+    /// from the `right` one, otherwise an error is returned.
     ///
     /// The `left` vertex is expected
     /// to be the root of the current graph, while the `right` vertex is the root
     /// of the graph being merged into the current one.
     ///
+    /// The graph being merged may allow a different number of edges per
+    /// vertex, for example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<4> = Sodg::empty(256);
+    /// g.add(0);
+    /// let mut extra : Sodg<16> = Sodg::empty(256);
+    /// extra.add(0);
+    /// extra.add(1);
+    /// extra.bind(0, 1, Label::Alpha(0));
+    /// g.merge(&extra, 0, 0).unwrap();
+    /// assert_eq!(2, g.len());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If it's impossible to merge, an error will be returned. This also
+    /// happens when a vertex would get more than `N` edges after the merge,
+    /// or when an alert, attached by [`Sodg::alert_on`], refuses the data
+    /// of a vertex being merged.
+    pub fn merge<const M: usize>(&mut self, g: &Sodg<M>, left: usize, right: usize) -> Result<()> {
+        self.merge_mapped(g, left, right).map(|_| ())
+    }
+
+    /// Merge another graph into the current one, just like [`Sodg::merge`]
+    /// does, but keep all new vertices in the static branch.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// let mut extra : Sodg<16> = Sodg::empty(256);
+    /// extra.add(0);
+    /// extra.add(1);
+    /// extra.bind(0, 1, Label::Alpha(0));
+    /// extra.put(1, &Hex::from(42));
+    /// g.merge_static(&extra, 0, 0).unwrap();
+    /// let v = g.kid(0, Label::Alpha(0)).unwrap();
+    /// assert_eq!(42, g.data(v).unwrap().to_i64().unwrap());
+    /// assert_eq!(2, g.len());
+    /// ```
+    ///
+    /// The [`Sodg::bind`] puts vertices into a fresh branch, when both
+    /// of them are static, and the branch is destroyed as soon as its
+    /// last data is taken. This is not what is needed when a template
+    /// is merged into the graph: its data may be read many times.
+    /// Here, no branch is allocated during the merge, and the vertices
+    /// which existed before it, including `left`, stay in their branches.
+    ///
     /// # Errors
     ///
     /// If it's impossible to merge, an error will be returned.
-    pub fn merge(&mut self, g: &Self, left: usize, right: usize) -> Result<()> {
+    pub fn merge_static<const M: usize>(
+        &mut self,
+        g: &Sodg<M>,
+        left: usize,
+        right: usize,
+    ) -> Result<()> {
+        let rules = Rules {
+            fixed: true,
+            ..Rules::default()
+        };
+        self.merge_checked(g, left, right, &rules).map(|_| ())
+    }
+
+    /// Merge another graph into the current one, just like [`Sodg::merge`]
+    /// does, but only along the edges of it that match the predicate.
+    ///
+    /// For example, here the edges starting with `+` are skipped,
+    /// together with the vertices they lead to:
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// let mut extra : Sodg<16> = Sodg::empty(256);
+    /// extra.add(0);
+    /// extra.add(1);
+    /// extra.bind(0, 1, Label::from_str("+meta").unwrap());
+    /// extra.add(2);
+    /// extra.bind(0, 2, Label::from_str("foo").unwrap());
+    /// g.merge_where(&extra, 0, 0, |_, _, a| !a.to_string().starts_with('+')).unwrap();
+    /// assert_eq!(2, g.len());
+    /// assert!(g.kid_str(0, "+meta").is_none());
+    /// ```
+    ///
+    /// The predicate gets the source vertex, the target vertex, and the label
+    /// of an edge of the right graph, just like in [`Sodg::slice_some`].
+    /// Unlike [`Sodg::merge`], it is not an error if some vertices
+    /// of the right graph are not merged.
+    ///
+    /// # Errors
+    ///
+    /// If it's impossible to merge, an error will be returned.
+    pub fn merge_where<const M: usize>(
+        &mut self,
+        g: &Sodg<M>,
+        left: usize,
+        right: usize,
+        p: impl Fn(usize, usize, Label) -> bool,
+    ) -> Result<()> {
+        let rules = Rules {
+            follow: &p,
+            whole: false,
+            ..Rules::default()
+        };
+        self.merge_checked(g, left, right, &rules).map(|_| ())
+    }
+
+    /// Merge another graph into the current one, just like [`Sodg::merge`]
+    /// does, but let the merged vertices with identical data share
+    /// a single copy of it.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// let mut extra : Sodg<16> = Sodg::empty(256);
+    /// extra.add(0);
+    /// extra.add(1);
+    /// extra.add(2);
+    /// extra.bind(0, 1, Label::Alpha(0));
+    /// extra.bind(0, 2, Label::Alpha(1));
+    /// extra.put(1, &Hex::from(42));
+    /// extra.put(2, &Hex::from(42));
+    /// g.merge_interned(&extra, 0, 0).unwrap();
+    /// let v = g.kid(0, Label::Alpha(1)).unwrap();
+    /// assert_eq!(42, g.data(v).unwrap().to_i64().unwrap());
+    /// ```
+    ///
+    /// The data is shared the same way as [`Sodg::put_shared`] does it,
+    /// while the persistence of each vertex stays as it was.
+    ///
+    /// # Errors
+    ///
+    /// If it's impossible to merge, an error will be returned.
+    pub fn merge_interned<const M: usize>(
+        &mut self,
+        g: &Sodg<M>,
+        left: usize,
+        right: usize,
+    ) -> Result<()> {
+        let mut merged: Vec<usize> = self.merge_mapped(g, left, right)?.into_values().collect();
+        merged.sort_unstable();
+        merged.dedup();
+        let mut seen: HashMap<Vec<u8>, SharedHex> = HashMap::new();
+        for v in merged {
+            let Some(vtx) = self.vertices.get_mut(v) else {
+                continue;
+            };
+            if vtx.branch == BRANCH_NONE || vtx.persistence == Persistence::Empty {
+                continue;
+            }
+            if let Some(d) = seen.get(vtx.data.bytes()) {
+                vtx.data = d.clone();
+            } else {
+                seen.insert(vtx.data.bytes().to_vec(), vtx.data.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Merge another graph into the current one, just like [`Sodg::merge`]
+    /// does, but rename the labels of its edges on the way.
+    ///
+    /// For example, here all labels get a prefix:
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// let mut extra : Sodg<16> = Sodg::empty(256);
+    /// extra.add(0);
+    /// extra.add(1);
+    /// extra.bind(0, 1, Label::from_str("foo").unwrap());
+    /// g.merge_relabel(&extra, 0, 0, |a| {
+    ///     Label::from_str(format!("x-{a}").as_str()).unwrap()
+    /// }).unwrap();
+    /// assert!(g.kid_str(0, "x-foo").is_some());
+    /// assert!(g.kid_str(0, "foo").is_none());
+    /// ```
+    ///
+    /// The kids of the right graph are matched with the kids of the current
+    /// one by their renamed labels.
+    ///
+    /// # Errors
+    ///
+    /// If it's impossible to merge, an error will be returned.
+    pub fn merge_relabel<const M: usize>(
+        &mut self,
+        g: &Sodg<M>,
+        left: usize,
+        right: usize,
+        relabel: impl Fn(Label) -> Label,
+    ) -> Result<()> {
+        let rules = Rules {
+            relabel: &relabel,
+            ..Rules::default()
+        };
+        self.merge_checked(g, left, right, &rules).map(|_| ())
+    }
+
+    /// Merge another graph into the current one and return
+    /// the vertices of it mapped to the vertices of the current one.
+    fn merge_mapped<const M: usize>(
+        &mut self,
+        g: &Sodg<M>,
+        left: usize,
+        right: usize,
+    ) -> Result<HashMap<usize, usize>> {
+        self.merge_checked(g, left, right, &Rules::default())
+    }
+
+    /// Merge another graph into the current one, following the rules,
+    /// and make sure all its vertices are merged, if the rules want so.
+    fn merge_checked<const M: usize>(
+        &mut self,
+        g: &Sodg<M>,
+        left: usize,
+        right: usize,
+        rules: &Rules,
+    ) -> Result<HashMap<usize, usize>> {
         let mut mapped = HashMap::new();
         let before = self.len();
-        self.merge_rec(g, left, right, &mut mapped)?;
-        let merged = mapped.len();
-        let scope = g.len();
-        if merged != scope {
-            let must = g.keys();
-            let seen = mapped.keys().copied().collect::<Vec<usize>>();
-            let missed: HashSet<usize> =
-                &HashSet::from_iter(must.clone()) - &HashSet::from_iter(seen.clone());
-            let mut ordered: Vec<usize> = missed.into_iter().collect();
-            ordered.sort_unstable();
-            return Err(anyhow!(
-                "Just {merged} vertices merged, out of {scope} (must={}, seen={}); maybe the right graph was not a tree? {} missed: {}",
-                must.len(), seen.len(),
-                ordered.len(), ordered.iter().map(|v| format!("ν{v}")).collect::<Vec<String>>().join(", ")
-            ));
+        let start = Instant::now();
+        let every = std::mem::take(&mut self.auto_collect);
+        let done = self.merge_rec(g, left, right, &mut mapped, start, rules);
+        self.auto_collect = every;
+        done?;
+        if rules.whole {
+            g.covered(right, &mapped.keys().copied().collect::<Vec<usize>>())?;
         }
         debug!(
-            "Merged all {merged} vertices into SODG of {}, making it have {} after the merge",
-            before,
-            self.len()
+            "Merged {} vertices out of {} into SODG of {before}, making it have {} after the merge, in {:?}",
+            mapped.len(),
+            g.len(),
+            self.len(),
+            start.elapsed()
         );
-        Ok(())
+        Ok(mapped)
+    }
+
+    /// Check whether [`Sodg::merge`] would succeed, without modifying
+    /// the current graph, and return how many vertices it would add.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// let mut extra : Sodg<16> = Sodg::empty(256);
+    /// extra.add(0);
+    /// extra.add(1);
+    /// extra.bind(0, 1, Label::Alpha(0));
+    /// assert_eq!(1, g.merge_dry_run(&extra, 0, 0).unwrap());
+    /// assert_eq!(1, g.len());
+    /// ```
+    ///
+    /// The merge is made on a clone of the graph, which is thrown away
+    /// afterwards. Thus, the dry run takes O(|self|) time and memory,
+    /// just like [`Sodg::clone`], no matter how small the right graph is.
+    ///
+    /// # Errors
+    ///
+    /// If it's impossible to merge, the same error as [`Sodg::merge`]
+    /// would return will be returned.
+    pub fn merge_dry_run<const M: usize>(
+        &self,
+        g: &Sodg<M>,
+        left: usize,
+        right: usize,
+    ) -> Result<usize> {
+        let mut copy = self.clone();
+        copy.listener = None;
+        copy.merge(g, left, right)?;
+        Ok(copy.len().saturating_sub(self.len()))
     }
 
-    /// Merge two trees recursively, ignoring the nodes already `mapped`.
+    /// Make sure all vertices of the graph are `seen` by a merge,
+    /// which started at the `right` one.
+    ///
+    /// # Errors
+    ///
+    /// If some vertices were not seen, an error will be returned.
+    fn covered(&self, right: usize, seen: &[usize]) -> Result<()> {
+        let scope = self.len();
+        if seen.len() == scope {
+            return Ok(());
+        }
+        let must = self.keys();
+        let missed: HashSet<usize> =
+            &HashSet::from_iter(must.clone()) - &seen.iter().copied().collect::<HashSet<_>>();
+        let mut ordered: Vec<usize> = missed.into_iter().collect();
+        ordered.sort_unstable();
+        Err(anyhow!(
+            "Just {} vertices merged, out of {scope} (must={}, seen={}); maybe some of them are not reachable from ν{right}? {} missed: {}",
+            seen.len(), must.len(), seen.len(),
+            ordered.len(), ordered.iter().map(|v| format!("ν{v}")).collect::<Vec<String>>().join(", ")
+        ))
+    }
+
+    /// Merge two graphs recursively, ignoring the nodes already `mapped`.
     ///
     /// The `right` vertex is mapped to the `left` vertex. The decisions about
     /// their kids are made recursively.
@@ -76,40 +388,79 @@ impl<const N: usize> Sodg<N> {
     /// # Errors
     ///
     /// If it's impossible to merge, an error will be returned.
+    ///
+    /// Only the edges of the right graph that match the `rules` are followed,
+    /// and their labels are renamed by them before they are matched and bound.
+    ///
+    /// The kids are merged in the order of their labels, thus new vertices
+    /// get the same IDs no matter in what order the edges were made.
+    ///
+    /// With the `trace` feature, the progress is logged every
+    /// `MERGE_PROGRESS` merged vertices, counting the time since `start`.
     #[allow(clippy::option_if_let_else)]
-    fn merge_rec(
+    #[cfg_attr(not(feature = "trace"), allow(clippy::only_used_in_recursion))]
+    fn merge_rec<const M: usize>(
         &mut self,
-        g: &Self,
+        g: &Sodg<M>,
         left: usize,
         right: usize,
         mapped: &mut HashMap<usize, usize>,
+        start: Instant,
+        rules: &Rules,
     ) -> Result<()> {
         if mapped.contains_key(&right) {
             return Ok(());
         }
         mapped.insert(right, left);
-        if g.vertices.get(right).unwrap().persistence != Persistence::Empty {
-            self.put(left, &g.vertices.get(right).unwrap().data);
+        #[cfg(feature = "trace")]
+        if mapped.len().is_multiple_of(MERGE_PROGRESS) {
+            trace!(
+                "#merge_rec: {} vertices merged in {:?}, at ν{right} → ν{left}",
+                mapped.len(),
+                start.elapsed()
+            );
+        }
+        let src = g.vertices.get(right).unwrap();
+        match src.persistence {
+            Persistence::Empty => {}
+            Persistence::Stored => self.try_put(left, &src.data)?,
+            Persistence::Taken => self.put_taken(left, &src.data),
         }
-        for (a, to) in g.kids(right) {
+        let mut kids = g.kids_owned(right);
+        kids.retain(|(a, to)| (rules.follow)(right, *to, *a));
+        for (a, _) in &mut kids {
+            *a = (rules.relabel)(*a);
+        }
+        kids.sort_unstable();
+        for (a, to) in &kids {
             let matched = if let Some(t) = self.kid(left, *a) {
                 t
+            } else if self.vertices.get(left).unwrap().edges.len() == N {
+                return Err(anyhow!(
+                    "Can't merge ν{right}.{a} into ν{left}, it already has {N} edges"
+                ));
             } else if let Some(t) = mapped.get(to) {
-                self.bind(left, *t, *a);
+                self.link(left, *t, *a, rules.fixed);
                 *t
             } else {
                 let id = self.next_id();
                 self.add(id);
-                self.bind(left, id, *a);
+                self.link(left, id, *a, rules.fixed);
                 id
             };
-            self.merge_rec(g, matched, *to, mapped)?;
+            self.merge_rec(g, matched, *to, mapped, start, rules)?;
         }
-        for (a, to) in g.kids(right) {
+        for (a, to) in &kids {
             if let Some(first) = self.kid(left, *a) {
                 if let Some(second) = mapped.get(to) {
                     if first != *second {
-                        self.join(first, *second);
+                        let gone = *second;
+                        self.join(first, gone, rules.fixed)?;
+                        for t in mapped.values_mut() {
+                            if *t == gone {
+                                *t = first;
+                            }
+                        }
                     }
                 }
             }
@@ -117,7 +468,55 @@ impl<const N: usize> Sodg<N> {
         Ok(())
     }
 
-    fn join(&mut self, left: usize, right: usize) {
+    /// Set vertex data, marking it as already taken, without
+    /// triggering any garbage collection.
+    pub(crate) fn put_taken(&mut self, v: usize, d: &Hex) {
+        let vtx = self.vertices.get_mut(v).unwrap();
+        if vtx.persistence == Persistence::Stored {
+            *self.stores.get_mut(vtx.branch).unwrap() -= 1;
+        }
+        vtx.persistence = Persistence::Taken;
+        vtx.data = SharedHex::new(d.clone());
+    }
+
+    /// Make an edge, letting [`Sodg::bind`] decide the branches
+    /// of the vertices, unless they are `fixed`.
+    fn link(&mut self, v1: usize, v2: usize, a: Label, fixed: bool) {
+        if fixed {
+            self.bind_fixed(v1, v2, a);
+        } else {
+            self.bind(v1, v2, a);
+        }
+    }
+
+    /// Join the `right` vertex into the `left` one: all edges leading
+    /// to `right` are redirected to `left`, its kids become kids of `left`,
+    /// and then `right` is removed.
+    ///
+    /// # Errors
+    ///
+    /// If `left` already has a kid with the same label as one of the kids
+    /// of `right`, or if it can't fit all of them into its `N` edges, an
+    /// error will be returned and nothing will be changed.
+    fn join(&mut self, left: usize, right: usize, fixed: bool) -> Result<()> {
+        let kids = self
+            .kids(right)
+            .map(|(a, v)| (*a, *v))
+            .collect::<Vec<(Label, usize)>>();
+        for e in &kids {
+            if self.kid(left, e.0).is_some() {
+                return Err(anyhow!(
+                    "Can't merge ν{right} into ν{left}, due to conflict in '{}'",
+                    e.0
+                ));
+            }
+        }
+        let edges = self.vertices.get(left).unwrap().edges.len();
+        if edges + kids.len() > N {
+            return Err(anyhow!(
+                "Can't merge ν{right} into ν{left}, it has {edges} edges, while {N} is the limit"
+            ));
+        }
         for v in self.keys() {
             let mut nv = self.vertices.get(v).unwrap().clone();
             for e in &self.vertices.get_mut(v).unwrap().edges {
@@ -127,22 +526,17 @@ impl<const N: usize> Sodg<N> {
             }
             self.vertices.insert(v, nv);
         }
-        let kids = self
-            .kids(right)
-            .map(|(a, v)| (*a, *v))
-            .collect::<Vec<(Label, usize)>>();
-        for e in kids {
-            assert!(
-                self.kid(left, e.0).is_none(),
-                "Can't merge ν{right} into ν{left}, due to conflict in '{}'",
-                e.0
-            );
-            self.bind(left, e.1, e.0);
+        for (a, to) in kids {
+            self.link(left, if to == right { left } else { to }, a, fixed);
         }
-        self.vertices.remove(right);
+        self.erase(right);
+        Ok(())
     }
 }
 
+#[cfg(test)]
+use crate::BRANCH_STATIC;
+
 #[cfg(test)]
 use std::str::FromStr;
 
@@ -152,7 +546,7 @@ fn merges_two_graphs() {
     g.add(0);
     g.add(1);
     g.bind(0, 1, Label::from_str("foo").unwrap());
-    let mut extra = Sodg::empty(256);
+    let mut extra: Sodg<16> = Sodg::empty(256);
     extra.add(0);
     extra.add(1);
     extra.bind(0, 1, Label::from_str("bar").unwrap());
@@ -165,7 +559,7 @@ fn merges_two_graphs() {
 #[test]
 fn merges_two_non_trees() {
     let mut g: Sodg<16> = Sodg::empty(256);
-    let mut extra = Sodg::empty(256);
+    let mut extra: Sodg<16> = Sodg::empty(256);
     extra.add(0);
     extra.add(42);
     extra.add(2);
@@ -184,7 +578,7 @@ fn merges_a_loop() {
     g.bind(0, 1, Label::from_str("a").unwrap());
     g.add(2);
     g.bind(1, 2, Label::from_str("b").unwrap());
-    let mut extra = Sodg::empty(256);
+    let mut extra: Sodg<16> = Sodg::empty(256);
     extra.add(0);
     extra.add(4);
     extra.bind(0, 4, Label::from_str("c").unwrap());
@@ -208,7 +602,7 @@ fn avoids_simple_duplicates() {
     g.add(0);
     g.add(5);
     g.bind(0, 5, Label::from_str("foo").unwrap());
-    let mut extra = Sodg::empty(256);
+    let mut extra: Sodg<16> = Sodg::empty(256);
     extra.add(0);
     extra.add(1);
     extra.bind(0, 1, Label::from_str("foo").unwrap());
@@ -230,7 +624,7 @@ fn keeps_existing_vertices_intact() {
     g.bind(1, 2, Label::from_str("bar").unwrap());
     g.add(3);
     g.bind(2, 3, Label::from_str("zzz").unwrap());
-    let mut extra = Sodg::empty(256);
+    let mut extra: Sodg<16> = Sodg::empty(256);
     extra.add(0);
     extra.add(5);
     extra.bind(0, 5, Label::from_str("foo").unwrap());
@@ -245,7 +639,7 @@ fn keeps_existing_vertices_intact() {
 fn merges_singletons() {
     let mut g: Sodg<16> = Sodg::empty(256);
     g.add(13);
-    let mut extra = Sodg::empty(256);
+    let mut extra: Sodg<16> = Sodg::empty(256);
     extra.add(13);
     g.merge(&extra, 13, 13).unwrap();
     assert_eq!(1, g.len());
@@ -279,20 +673,87 @@ fn merges_large_loop() {
     assert_eq!(extra.len(), g.len());
 }
 
-#[cfg(test)]
-use crate::Hex;
-
 #[test]
 fn merges_data() {
     let mut g: Sodg<16> = Sodg::empty(256);
     g.add(1);
-    let mut extra = Sodg::empty(256);
+    let mut extra: Sodg<16> = Sodg::empty(256);
     extra.add(1);
     extra.put(1, &Hex::from(42));
     g.merge(&extra, 1, 1).unwrap();
     assert_eq!(42, g.data(1).unwrap().to_i64().unwrap());
 }
 
+#[test]
+fn predicts_merge_result() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("foo").unwrap());
+    let mut extra: Sodg<16> = Sodg::empty(256);
+    extra.add(0);
+    extra.add(1);
+    extra.bind(0, 1, Label::from_str("foo").unwrap());
+    extra.add(2);
+    extra.bind(1, 2, Label::from_str("bar").unwrap());
+    extra.add(3);
+    extra.bind(0, 3, Label::from_str("xyz").unwrap());
+    let before = g.len();
+    let predicted = g.merge_dry_run(&extra, 0, 0).unwrap();
+    assert_eq!(before, g.len());
+    g.merge(&extra, 0, 0).unwrap();
+    assert_eq!(2, predicted);
+    assert_eq!(predicted, g.len() - before);
+}
+
+#[test]
+fn predicts_merge_failure() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    let mut extra: Sodg<16> = Sodg::empty(256);
+    extra.add(0);
+    extra.add(42);
+    let r = g.merge_dry_run(&extra, 0, 0);
+    assert_eq!(
+        g.merge(&extra, 0, 0).err().unwrap().to_string(),
+        r.err().unwrap().to_string()
+    );
+}
+
+#[test]
+fn refuses_data_rejected_by_alerts() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.alert_on(crate::alerts::no_nan_floats());
+    g.add(0);
+    let mut extra: Sodg<16> = Sodg::empty(256);
+    extra.add(0);
+    extra.add(1);
+    extra.bind(0, 1, Label::Alpha(0));
+    extra.put(1, &Hex::from(f64::NAN));
+    assert!(g.merge(&extra, 0, 0).is_err());
+}
+
+#[test]
+fn merges_taken_data() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    let mut extra: Sodg<16> = Sodg::empty(256);
+    extra.add(0);
+    extra.add(1);
+    extra.bind(0, 1, Label::from_str("a").unwrap());
+    extra.add(2);
+    extra.bind(0, 2, Label::from_str("b").unwrap());
+    extra.put(1, &Hex::from(1));
+    extra.put(2, &Hex::from(2));
+    extra.data(1);
+    g.merge(&extra, 0, 0).unwrap();
+    let a = g.kid(0, Label::from_str("a").unwrap()).unwrap();
+    let b = g.kid(0, Label::from_str("b").unwrap()).unwrap();
+    assert!(g.vertices.get(a).unwrap().persistence == Persistence::Taken);
+    assert!(g.vertices.get(b).unwrap().persistence == Persistence::Stored);
+    assert_eq!(1, g.data(a).unwrap().to_i64().unwrap());
+    assert_eq!(&1, g.stores.get(g.vertices.get(b).unwrap().branch).unwrap());
+}
+
 #[test]
 fn understands_same_name_kids() {
     let mut g: Sodg<16> = Sodg::empty(256);
@@ -301,7 +762,7 @@ fn understands_same_name_kids() {
     g.bind(0, 1, Label::from_str("a").unwrap());
     g.add(2);
     g.bind(1, 2, Label::from_str("x").unwrap());
-    let mut extra = Sodg::empty(256);
+    let mut extra: Sodg<16> = Sodg::empty(256);
     extra.add(0);
     extra.add(1);
     extra.bind(0, 1, Label::from_str("b").unwrap());
@@ -317,7 +778,7 @@ fn understands_same_name_kids() {
 fn merges_into_empty_graph() {
     let mut g: Sodg<16> = Sodg::empty(256);
     g.add(1);
-    let mut extra = Sodg::empty(256);
+    let mut extra: Sodg<16> = Sodg::empty(256);
     extra.add(1);
     extra.add(2);
     extra.add(3);
@@ -333,7 +794,7 @@ fn merges_into_empty_graph() {
 fn mixed_injection() {
     let mut g: Sodg<16> = Sodg::empty(256);
     g.add(4);
-    let mut extra = Sodg::empty(256);
+    let mut extra: Sodg<16> = Sodg::empty(256);
     extra.add(4);
     extra.put(4, &Hex::from(4));
     extra.add(5);
@@ -352,7 +813,7 @@ fn zero_to_zero() {
     g.bind(1, 0, Label::from_str("back").unwrap());
     g.add(2);
     g.bind(0, 2, Label::from_str("b").unwrap());
-    let mut extra = Sodg::empty(256);
+    let mut extra: Sodg<16> = Sodg::empty(256);
     extra.add(0);
     extra.add(1);
     extra.bind(0, 1, Label::from_str("c").unwrap());
@@ -369,7 +830,7 @@ fn finds_siblings() {
     g.bind(0, 1, Label::from_str("a").unwrap());
     g.add(2);
     g.bind(0, 2, Label::from_str("b").unwrap());
-    let mut extra = Sodg::empty(256);
+    let mut extra: Sodg<16> = Sodg::empty(256);
     extra.add(0);
     extra.add(1);
     extra.bind(0, 1, Label::from_str("b").unwrap());
@@ -390,10 +851,301 @@ fn two_big_graphs() {
     )
     .deploy_to(&mut g)
     .unwrap();
-    let mut extra = Sodg::empty(256);
+    let mut extra: Sodg<16> = Sodg::empty(256);
     Script::from_str("ADD(0); ADD(1); BIND(0, 1, bar); BIND(1, 0, back);")
         .deploy_to(&mut extra)
         .unwrap();
     g.merge(&extra, 0, 0).unwrap();
     assert_eq!(4, g.len());
 }
+
+#[test]
+fn merges_graphs_of_different_widths() {
+    let mut narrow: Sodg<2> = Sodg::empty(256);
+    narrow.add(0);
+    narrow.add(1);
+    narrow.bind(0, 1, Label::from_str("foo").unwrap());
+    let mut wide: Sodg<8> = Sodg::empty(256);
+    wide.add(0);
+    wide.add(1);
+    wide.bind(0, 1, Label::from_str("bar").unwrap());
+    let mut g = wide.clone();
+    g.merge(&narrow, 0, 0).unwrap();
+    assert_eq!(3, g.len());
+    narrow.merge(&wide, 0, 0).unwrap();
+    assert_eq!(3, narrow.len());
+    assert!(narrow.kid_str(0, "bar").is_some());
+}
+
+#[test]
+fn refuses_to_merge_too_many_edges() {
+    let mut g: Sodg<2> = Sodg::empty(256);
+    g.add(0);
+    let mut extra: Sodg<8> = Sodg::empty(256);
+    extra.add(0);
+    for i in 1..4 {
+        extra.add(i);
+        extra.bind(0, i, Label::Alpha(i));
+    }
+    assert!(g.merge_dry_run(&extra, 0, 0).is_err());
+    assert!(g.merge(&extra, 0, 0).is_err());
+}
+
+#[test]
+fn keeps_loops_of_joined_vertices() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.add(2);
+    g.bind(0, 2, Label::Alpha(1));
+    let mut extra: Sodg<16> = Sodg::empty(256);
+    extra.add(0);
+    extra.add(1);
+    extra.bind(0, 1, Label::Alpha(0));
+    extra.bind(0, 1, Label::Alpha(1));
+    extra.bind(1, 1, Label::Alpha(2));
+    g.merge(&extra, 0, 0).unwrap();
+    assert_eq!(2, g.len());
+    let v = g.kid(0, Label::Alpha(0)).unwrap();
+    assert_eq!(Some(v), g.kid(0, Label::Alpha(1)));
+    assert_eq!(Some(v), g.kid(v, Label::Alpha(2)));
+}
+
+#[test]
+fn refuses_to_join_conflicting_vertices() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.add(2);
+    g.bind(0, 2, Label::Alpha(1));
+    g.add(3);
+    g.bind(1, 3, Label::Alpha(5));
+    g.add(4);
+    g.bind(2, 4, Label::Alpha(5));
+    let mut extra: Sodg<16> = Sodg::empty(256);
+    extra.add(0);
+    extra.add(1);
+    extra.bind(0, 1, Label::Alpha(0));
+    extra.bind(0, 1, Label::Alpha(1));
+    assert!(g.merge(&extra, 0, 0).is_err());
+}
+
+#[test]
+fn refuses_to_join_into_too_many_edges() {
+    let mut g: Sodg<2> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.add(2);
+    g.bind(0, 2, Label::Alpha(1));
+    g.add(3);
+    g.bind(1, 3, Label::Alpha(5));
+    g.add(4);
+    g.bind(1, 4, Label::Alpha(6));
+    g.add(5);
+    g.bind(2, 5, Label::Alpha(7));
+    let mut extra: Sodg<2> = Sodg::empty(256);
+    extra.add(0);
+    extra.add(1);
+    extra.bind(0, 1, Label::Alpha(0));
+    extra.bind(0, 1, Label::Alpha(1));
+    assert!(g.merge(&extra, 0, 0).is_err());
+}
+
+#[test]
+fn merges_into_static_branch() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    let mut extra: Sodg<16> = Sodg::empty(256);
+    extra.add(0);
+    extra.add(1);
+    extra.bind(0, 1, Label::from_str("foo").unwrap());
+    extra.add(2);
+    extra.bind(1, 2, Label::from_str("bar").unwrap());
+    extra.put(2, &Hex::from(42));
+    g.merge_static(&extra, 0, 0).unwrap();
+    assert_eq!(3, g.len());
+    for v in g.keys() {
+        assert_eq!(BRANCH_STATIC, g.vertices.get(v).unwrap().branch);
+    }
+    assert!(g.branches.iter().skip(2).all(|(_, m)| m.is_empty()));
+    let v = g.find(0, "foo.bar", &crate::DeadRelay::default()).unwrap();
+    assert_eq!(42, g.data(v).unwrap().to_i64().unwrap());
+    assert!(g.collect().is_empty());
+    assert_eq!(3, g.len());
+    assert!(g.check_invariants().is_ok());
+}
+
+#[test]
+fn keeps_branches_of_existing_vertices_in_static_merge() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("foo").unwrap());
+    let before = g.vertices.get(0).unwrap().branch;
+    assert!(before > BRANCH_STATIC);
+    let mut extra: Sodg<16> = Sodg::empty(256);
+    extra.add(0);
+    extra.add(1);
+    extra.bind(0, 1, Label::from_str("foo").unwrap());
+    extra.add(2);
+    extra.bind(1, 2, Label::from_str("bar").unwrap());
+    extra.add(3);
+    extra.bind(0, 3, Label::from_str("xyz").unwrap());
+    g.merge_static(&extra, 0, 0).unwrap();
+    assert_eq!(4, g.len());
+    assert_eq!(before, g.vertices.get(0).unwrap().branch);
+    assert_eq!(before, g.vertices.get(1).unwrap().branch);
+    for loc in ["foo.bar", "xyz"] {
+        let v = g.find(0, loc, &crate::DeadRelay::default()).unwrap();
+        assert_eq!(BRANCH_STATIC, g.vertices.get(v).unwrap().branch);
+    }
+    assert_eq!(vec![0, 1], g.branch_members(before));
+    assert!(g.check_invariants().is_ok());
+}
+
+#[test]
+fn merges_with_reproducible_ids() {
+    let labels = ["foo", "bar", "zzz", "abc"];
+    let mut xmls = vec![];
+    for order in [[0, 1, 2, 3], [3, 2, 1, 0]] {
+        let mut extra: Sodg<16> = Sodg::empty(256);
+        extra.add(0);
+        for i in order {
+            extra.add(i + 1);
+            extra.bind(0, i + 1, Label::from_str(labels[i]).unwrap());
+            extra.add(i + 10);
+            extra.bind(i + 1, i + 10, Label::Alpha(0));
+        }
+        let mut g: Sodg<16> = Sodg::empty(256);
+        g.add(0);
+        g.merge(&extra, 0, 0).unwrap();
+        xmls.push(g.to_xml().unwrap());
+    }
+    assert_eq!(xmls[0], xmls[1]);
+}
+
+#[test]
+fn merges_some_edges_only() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    let mut extra: Sodg<16> = Sodg::empty(256);
+    for v in 0..5 {
+        extra.add(v);
+    }
+    extra.bind(0, 1, Label::from_str("foo").unwrap());
+    extra.bind(0, 2, Label::from_str("+bar").unwrap());
+    extra.bind(2, 3, Label::Alpha(0));
+    extra.bind(1, 4, Label::from_str("+xyz").unwrap());
+    g.merge_where(&extra, 0, 0, |_, _, a| !a.to_string().starts_with('+'))
+        .unwrap();
+    assert_eq!(2, g.len());
+    let foo = g.kid_str(0, "foo").unwrap();
+    assert!(g.kid_str(0, "+bar").is_none());
+    assert!(g.kid_str(foo, "+xyz").is_none());
+    assert!(g.check_invariants().is_ok());
+}
+
+#[test]
+fn merges_a_diamond() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    let mut extra: Sodg<16> = Sodg::empty(256);
+    for v in 0..4 {
+        extra.add(v);
+    }
+    extra.bind(0, 1, Label::from_str("left").unwrap());
+    extra.bind(0, 2, Label::from_str("right").unwrap());
+    extra.bind(1, 3, Label::Alpha(0));
+    extra.bind(2, 3, Label::Alpha(0));
+    extra.put(3, &Hex::from(42));
+    g.merge(&extra, 0, 0).unwrap();
+    assert_eq!(4, g.len());
+    let left = g.kid(0, Label::from_str("left").unwrap()).unwrap();
+    let right = g.kid(0, Label::from_str("right").unwrap()).unwrap();
+    let bottom = g.kid(left, Label::Alpha(0)).unwrap();
+    assert_eq!(Some(bottom), g.kid(right, Label::Alpha(0)));
+    assert_eq!(
+        1,
+        g.keys()
+            .into_iter()
+            .filter(|v| g.data_ref(*v).is_some())
+            .count()
+    );
+    assert!(g.check_invariants().is_ok());
+}
+
+#[test]
+fn interns_identical_data() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    let mut extra: Sodg<16> = Sodg::empty(256);
+    extra.add(0);
+    for v in 1..4 {
+        extra.add(v);
+        extra.bind(0, v, Label::Alpha(v));
+    }
+    extra.put(1, &Hex::from_str_bytes("constant"));
+    extra.put(2, &Hex::from_str_bytes("constant"));
+    extra.put(3, &Hex::from_str_bytes("another"));
+    g.merge_interned(&extra, 0, 0).unwrap();
+    let first = g.kid(0, Label::Alpha(1)).unwrap();
+    let second = g.kid(0, Label::Alpha(2)).unwrap();
+    let third = g.kid(0, Label::Alpha(3)).unwrap();
+    let data = |v: usize| g.vertices.get(v).unwrap().data.clone();
+    assert!(SharedHex::ptr_eq(&data(first), &data(second)));
+    assert!(!SharedHex::ptr_eq(&data(first), &data(third)));
+    assert_eq!(Hex::from_str_bytes("constant"), g.data(second).unwrap());
+    assert!(g.check_invariants().is_ok());
+}
+
+#[cfg(feature = "trace")]
+#[test]
+fn reports_merge_progress() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    let mut extra: Sodg<16> = Sodg::empty(256);
+    extra.add(0);
+    for v in 1..15 {
+        extra.add(v);
+        extra.bind(v - 1, v, Label::Alpha(0));
+    }
+    let lines = crate::capture_logs(|| g.merge(&extra, 0, 0).unwrap());
+    assert_eq!(15, g.len());
+    assert!(
+        lines
+            .iter()
+            .any(|l| l.starts_with("#merge_rec: 10 vertices merged in ")),
+        "{lines:?}"
+    );
+}
+
+#[test]
+fn merges_with_relabeling() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("FOO").unwrap());
+    let mut extra: Sodg<16> = Sodg::empty(256);
+    extra.add(0);
+    extra.add(1);
+    extra.bind(0, 1, Label::from_str("foo").unwrap());
+    extra.add(2);
+    extra.bind(1, 2, Label::from_str("bar").unwrap());
+    extra.add(3);
+    extra.bind(0, 3, Label::Alpha(0));
+    g.merge_relabel(&extra, 0, 0, |a| match a {
+        Label::Str(cs) => Label::Str(cs.map(|c| c.to_ascii_uppercase())),
+        _ => a,
+    })
+    .unwrap();
+    assert_eq!(4, g.len());
+    assert_eq!(Some(1), g.kid_str(0, "FOO"));
+    assert!(g.kid_str(0, "foo").is_none());
+    let bar = g.find(0, "FOO.BAR", &crate::DeadRelay::default()).unwrap();
+    assert_eq!(Some(bar), g.kid_str(1, "BAR"));
+    assert!(g.kid_str(1, "bar").is_none());
+    assert!(g.kid(0, Label::Alpha(0)).is_some());
+}
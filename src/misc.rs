@@ -18,18 +18,23 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::Sodg;
+use crate::{Label, Persistence, Sodg, VertexIds, VertexSummary, BRANCH_NONE};
+use std::collections::HashMap;
+use std::hash::BuildHasher;
 
 impl<const N: usize> Sodg<N> {
     /// Get total number of vertices in the graph.
+    ///
+    /// The number is maintained by the graph as vertices are added
+    /// and removed, so it is not counted on every call.
     #[must_use]
-    pub fn len(&self) -> usize {
-        self.keys().len()
+    pub const fn len(&self) -> usize {
+        self.alive
     }
 
     /// Is it empty?
     #[must_use]
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
@@ -42,6 +47,129 @@ impl<const N: usize> Sodg<N> {
             .map(|(v, _)| v)
             .collect::<Vec<usize>>()
     }
+
+    /// Get keys of all vertices alive, which match the predicate.
+    ///
+    /// For example, here are the vertices with data:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.put(1, &Hex::from(42));
+    /// assert_eq!(vec![1], g.keys_where(|_, s| s.data.is_some()));
+    /// ```
+    ///
+    /// The predicate gets the ID of a vertex and its [`VertexSummary`].
+    #[must_use]
+    pub fn keys_where(&self, f: impl Fn(usize, &VertexSummary) -> bool) -> Vec<usize> {
+        self.vertices
+            .iter()
+            .filter(|(_, vtx)| vtx.branch != BRANCH_NONE)
+            .filter(|(v, vtx)| {
+                f(
+                    *v,
+                    &VertexSummary {
+                        branch: vtx.branch,
+                        degree: vtx.edges.len(),
+                        data: (vtx.persistence != Persistence::Empty).then(|| vtx.data.len()),
+                        taken: vtx.persistence == Persistence::Taken,
+                    },
+                )
+            })
+            .map(|(v, _)| v)
+            .collect()
+    }
+
+    /// Iterate IDs of live vertices, without allocating a `Vec`
+    /// as [`Sodg::keys`] does.
+    ///
+    /// The same iterator is made by `for v in &g`.
+    #[must_use]
+    pub const fn iter(&self) -> VertexIds<'_, N> {
+        VertexIds {
+            iter: self.vertices.iter(),
+        }
+    }
+
+    /// Get IDs of all vertices in the given branch, in the order they
+    /// were added to it.
+    ///
+    /// # Panics
+    ///
+    /// If the branch is out of range, it will panic.
+    #[must_use]
+    pub fn branch_members(&self, branch: usize) -> Vec<usize> {
+        self.branches
+            .get(branch)
+            .unwrap()
+            .into_iter()
+            .filter(|v| self.vertices.get(*v).unwrap().branch == branch)
+            .collect()
+    }
+}
+
+impl<'a, const N: usize> IntoIterator for &'a Sodg<N> {
+    type Item = usize;
+    type IntoIter = VertexIds<'a, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<const N: usize> Iterator for VertexIds<'_, N> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        self.iter
+            .find(|(_, vtx)| vtx.branch != BRANCH_NONE)
+            .map(|(v, _)| v)
+    }
+}
+
+/// Replace IDs of vertices in place, according to the map from old IDs
+/// to new ones.
+///
+/// For example:
+///
+/// ```
+/// use std::collections::HashMap;
+/// let map = HashMap::from([(1, 10), (2, 20)]);
+/// let mut ids = [1, 2, 3];
+/// sodg::remap_ids(&map, &mut ids);
+/// assert_eq!([10, 20, 3], ids);
+/// ```
+///
+/// IDs that are not in the map stay as they are.
+pub fn remap_ids<S: BuildHasher>(map: &HashMap<usize, usize, S>, ids: &mut [usize]) {
+    for v in ids.iter_mut() {
+        if let Some(to) = map.get(v) {
+            *v = *to;
+        }
+    }
+}
+
+/// Replace IDs of vertices in labeled pairs in place, just
+/// like [`remap_ids`] does, keeping the labels.
+///
+/// For example:
+///
+/// ```
+/// use std::collections::HashMap;
+/// use sodg::Label;
+/// let map = HashMap::from([(1, 10)]);
+/// let mut kids = [(Label::Alpha(0), 1), (Label::Alpha(1), 2)];
+/// sodg::remap_labeled(&map, &mut kids);
+/// assert_eq!([(Label::Alpha(0), 10), (Label::Alpha(1), 2)], kids);
+/// ```
+pub fn remap_labeled<S: BuildHasher>(map: &HashMap<usize, usize, S>, kids: &mut [(Label, usize)]) {
+    for (_, v) in kids.iter_mut() {
+        if let Some(to) = map.get(v) {
+            *v = *to;
+        }
+    }
 }
 
 #[test]
@@ -49,3 +177,80 @@ fn counts_vertices() {
     let g: Sodg<16> = Sodg::empty(256);
     assert_eq!(0, g.len());
 }
+
+#[test]
+fn remaps_ids_in_place() {
+    let map = HashMap::from([(0, 5), (7, 1)]);
+    let mut ids = vec![7, 0, 3, 7];
+    remap_ids(&map, &mut ids);
+    assert_eq!(vec![1, 5, 3, 1], ids);
+    let mut kids = vec![(Label::Greek('ρ'), 0), (Label::Alpha(0), 9)];
+    remap_labeled(&map, &mut kids);
+    assert_eq!(vec![(Label::Greek('ρ'), 5), (Label::Alpha(0), 9)], kids);
+}
+
+#[test]
+fn iterates_live_vertices() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(7);
+    g.add(3);
+    g.add(100);
+    let mut ids: Vec<usize> = (&g).into_iter().collect();
+    ids.sort_unstable();
+    let mut keys = g.keys();
+    keys.sort_unstable();
+    assert_eq!(keys, ids);
+    assert_eq!(vec![3, 7, 100], ids);
+    assert_eq!(3, g.iter().count());
+}
+
+#[test]
+fn counts_vertices_on_the_fly() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    let check = |g: &Sodg<16>| assert_eq!(g.keys().len(), g.len());
+    for v in 0..10 {
+        g.add(v);
+        g.add(v);
+        check(&g);
+    }
+    g.bind(0, 1, Label::Alpha(0));
+    g.bind(1, 2, Label::Alpha(0));
+    g.bind(3, 4, Label::Alpha(0));
+    g.put(4, &crate::Hex::from(42));
+    check(&g);
+    g.remove(5);
+    g.remove(5);
+    check(&g);
+    g.data(4);
+    check(&g);
+    g.collect();
+    check(&g);
+    g.add(5);
+    g.add(3);
+    check(&g);
+    g.bind(0, 99, Label::Alpha(1));
+    check(&g);
+    let c = g.clone();
+    check(&c);
+    assert!(!g.is_empty());
+}
+
+#[test]
+fn selects_keys_by_summary() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    for v in 0..4 {
+        g.add(v);
+    }
+    g.bind(0, 1, Label::Alpha(0));
+    g.bind(0, 2, Label::Alpha(1));
+    g.bind(1, 2, Label::Alpha(0));
+    g.put(2, &crate::Hex::from_str_bytes("hello"));
+    g.put(3, &crate::Hex::from(1));
+    g.data(3);
+    let mut with_data = g.keys_where(|_, s| s.data.is_some());
+    with_data.sort_unstable();
+    assert_eq!(vec![2, 3], with_data);
+    assert_eq!(vec![0], g.keys_where(|_, s| s.degree >= 2));
+    assert_eq!(vec![3], g.keys_where(|_, s| s.taken));
+    assert_eq!(vec![2], g.keys_where(|_, s| s.data == Some(5)));
+}
@@ -26,6 +26,9 @@ impl<const N: usize> Sodg<N> {
     /// This ID will never be returned by [`Sodg::next_id`] again. Also, this ID will not
     /// be equal to any of the existing IDs of vertices.
     ///
+    /// The counter of IDs is not saved by [`Sodg::save`]. After [`Sodg::load`],
+    /// the IDs are counted again from zero, skipping the vertices loaded.
+    ///
     /// # Panics
     ///
     /// May panic if not enough IDs are available.
@@ -87,3 +90,18 @@ fn next_id_after_zero() {
     assert_eq!(1, g.next_id());
     assert_eq!(2, g.next_id());
 }
+
+#[test]
+fn next_id_after_load() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(100);
+    let tmp = tempfile::TempDir::new().unwrap();
+    let file = tmp.path().join("foo.sodg");
+    g.save(file.as_path()).unwrap();
+    let mut after: Sodg<16> = Sodg::load(file.as_path()).unwrap();
+    let ids: Vec<usize> = (0..100).map(|_| after.next_id()).collect();
+    assert!(!ids.contains(&0));
+    assert!(!ids.contains(&100));
+    assert_eq!(101, *ids.last().unwrap());
+}
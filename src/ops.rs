@@ -18,11 +18,13 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::{Hex, Label};
+use crate::{Event, Hex, Label, Lambda, SharedHex};
 use crate::{Persistence, Sodg, BRANCH_NONE, BRANCH_STATIC};
-use anyhow::Context;
+use anyhow::{anyhow, Context, Result};
 #[cfg(debug_assertions)]
 use log::trace;
+use std::collections::HashSet;
+use std::str::FromStr;
 
 impl<const N: usize> Sodg<N> {
     /// Add a new vertex `v1` to itself.
@@ -38,18 +40,98 @@ impl<const N: usize> Sodg<N> {
     /// g.bind(0, 42, Label::from_str("hello").unwrap());
     /// ```
     ///
-    /// If vertex `v1` already exists in the graph, nothing will happen.
+    /// If vertex `v1` already exists in the graph, it is moved out
+    /// of its branch to the static one, together with its data.
     ///
     /// # Panics
     ///
     /// If alerts trigger any error, the error will be returned here.
     #[inline]
     pub fn add(&mut self, v1: usize) {
-        self.vertices.get_mut(v1).unwrap().branch = 1;
+        let vtx = self.vertices.get_mut(v1).unwrap();
+        let before = vtx.branch;
+        if before == BRANCH_NONE {
+            self.alive += 1;
+        }
+        vtx.branch = BRANCH_STATIC;
+        self.shade(None, v1);
+        if before > BRANCH_STATIC {
+            let members = self.branches.get_mut(before).unwrap();
+            let rest: Vec<usize> = members.into_iter().filter(|m| *m != v1).collect();
+            *members = microstack::Stack::from_vec(rest);
+            self.move_store(v1, before);
+        }
+        self.emit(|| Event::Added(v1));
+        self.verify();
         #[cfg(debug_assertions)]
         trace!("#add: vertex ν{v1} added");
     }
 
+    /// Add a new vertex `v1` and place it into the given branch,
+    /// instead of letting [`Sodg::bind`] decide.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Sodg;
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add_in_branch(1, 5).unwrap();
+    /// g.add_in_branch(2, 5).unwrap();
+    /// assert_eq!(vec![1, 2], g.branch_members(5));
+    /// ```
+    ///
+    /// Be careful: the vertex will be destroyed together with all other
+    /// members of the branch, as soon as the last piece of data in the branch
+    /// is taken, even if the vertex is still used by other branches. If the
+    /// vertex already exists in another branch, it is moved out of it, together
+    /// with its data.
+    ///
+    /// # Errors
+    ///
+    /// If the branch is out of range or is full, an error will be returned.
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v1` is out of the capacity of the graph, it will panic.
+    pub fn add_in_branch(&mut self, v1: usize, branch: usize) -> Result<()> {
+        let max = self.branches.capacity();
+        if branch == BRANCH_NONE || branch >= max {
+            return Err(anyhow!(
+                "Can't put ν{v1} into branch no.{branch}, it must be in [{BRANCH_STATIC}..{max})"
+            ));
+        }
+        let before = self.vertices.get(v1).unwrap().branch;
+        if before == branch {
+            return Ok(());
+        }
+        if branch > BRANCH_STATIC {
+            self.branches
+                .get_mut(branch)
+                .unwrap()
+                .try_push(v1)
+                .map_err(|e| anyhow!("Can't put ν{v1} into branch no.{branch}: {e}"))?;
+        }
+        if before > BRANCH_STATIC {
+            let members = self.branches.get_mut(before).unwrap();
+            let rest: Vec<usize> = members.into_iter().filter(|m| *m != v1).collect();
+            *members = microstack::Stack::from_vec(rest);
+        }
+        if before == BRANCH_NONE {
+            self.alive += 1;
+            self.shade(None, v1);
+        }
+        let vtx = self.vertices.get_mut(v1).unwrap();
+        vtx.branch = branch;
+        if vtx.persistence == Persistence::Stored {
+            *self.stores.get_mut(before).unwrap() -= 1;
+            *self.stores.get_mut(branch).unwrap() += 1;
+        }
+        self.emit(|| Event::Added(v1));
+        #[cfg(debug_assertions)]
+        trace!("#add_in_branch: vertex ν{v1} added to branch no.{branch}");
+        Ok(())
+    }
+
     /// Make an edge `e1` from vertex `v1` to vertex `v2` and put `a` label on it.
     ///
     /// For example:
@@ -77,42 +159,561 @@ impl<const N: usize> Sodg<N> {
     /// If alerts trigger any error, the error will be returned here.
     #[inline]
     pub fn bind(&mut self, v1: usize, v2: usize, a: Label) {
-        let mut ours = self.vertices.get(v1).unwrap().branch;
+        let ours = self.vertices.get(v1).unwrap().branch;
         let theirs = self.vertices.get(v2).unwrap().branch;
         let vtx1 = self.vertices.get_mut(v1).unwrap();
         vtx1.edges.insert(a, v2);
-        if ours == BRANCH_STATIC {
-            if theirs == BRANCH_STATIC {
-                for b in self.branches.iter_mut() {
-                    if b.1.is_empty() {
-                        b.1.push(v1);
-                        ours = b.0;
-                        vtx1.branch = ours;
-                        break;
-                    }
+        #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+        let (b1, b2) = if ours == BRANCH_STATIC && theirs == BRANCH_STATIC {
+            let b = self.free_branch();
+            self.branches.get_mut(b).unwrap().push(v1);
+            self.vertices.get_mut(v1).unwrap().branch = b;
+            self.vertices.get_mut(v2).unwrap().branch = b;
+            self.branches.get_mut(b).unwrap().push(v2);
+            self.move_store(v1, BRANCH_STATIC);
+            self.move_store(v2, BRANCH_STATIC);
+            (b, b)
+        } else if ours == BRANCH_STATIC && theirs != BRANCH_NONE {
+            vtx1.branch = theirs;
+            self.branches.get_mut(theirs).unwrap().push(v1);
+            self.move_store(v1, BRANCH_STATIC);
+            (theirs, theirs)
+        } else if ours != BRANCH_NONE && ours != BRANCH_STATIC && theirs == BRANCH_STATIC {
+            self.vertices.get_mut(v2).unwrap().branch = ours;
+            self.branches.get_mut(ours).unwrap().push(v2);
+            self.move_store(v2, BRANCH_STATIC);
+            (ours, ours)
+        } else {
+            (ours, theirs)
+        };
+        self.shade(Some(v1), v2);
+        self.forget_finds();
+        self.emit(|| Event::Bound(v1, v2, a));
+        self.verify();
+        #[cfg(debug_assertions)]
+        trace!("#bind: edge added ν{v1}(b={b1}).{a} → ν{v2}(b={b2})");
+    }
+
+    /// Make an edge from vertex `v1` to vertex `v2`, just like
+    /// [`Sodg::bind`] does, but keep both vertices in their branches.
+    pub(crate) fn bind_fixed(&mut self, v1: usize, v2: usize, a: Label) {
+        self.vertices.get_mut(v1).unwrap().edges.insert(a, v2);
+        self.shade(Some(v1), v2);
+        self.forget_finds();
+        self.emit(|| Event::Bound(v1, v2, a));
+        self.verify();
+        #[cfg(debug_assertions)]
+        trace!("#bind_fixed: edge added ν{v1}.{a} → ν{v2}");
+    }
+
+    /// Make an edge from vertex `v1` to vertex `v2`, adding
+    /// either of them first if it is absent.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.bind_auto(0, 1, Label::Alpha(0));
+    /// assert_eq!(2, g.len());
+    /// assert_eq!(Some(1), g.kid(0, Label::Alpha(0)));
+    /// ```
+    ///
+    /// Unlike [`Sodg::bind`], which expects both vertices to be added
+    /// already, this lets you bind a child before declaring it. The vertices
+    /// already present are not touched, they stay in their branches.
+    ///
+    /// # Panics
+    ///
+    /// If either vertex is out of capacity, it will panic.
+    #[inline]
+    pub fn bind_auto(&mut self, v1: usize, v2: usize, a: Label) {
+        for v in [v1, v2] {
+            if self.vertices.get(v).unwrap().branch == BRANCH_NONE {
+                self.add(v);
+            }
+        }
+        self.bind(v1, v2, a);
+    }
+
+    /// Make an edge from vertex `v1` to vertex `v2`, unless it would
+    /// close a cycle.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind_acyclic(0, 1, Label::Alpha(0)).unwrap();
+    /// assert!(g.bind_acyclic(1, 0, Label::Greek('ρ')).is_err());
+    /// assert!(g.kid(1, Label::Greek('ρ')).is_none());
+    /// ```
+    ///
+    /// The edge closes a cycle if `v1` is reachable from `v2`, which
+    /// is checked by [`Sodg::shortest_path`]. Thus, this costs as much
+    /// as a search through the graph.
+    ///
+    /// # Errors
+    ///
+    /// If either vertex is absent, or the edge would close a cycle,
+    /// an `Err` will be returned and the graph stays as it was.
+    pub fn bind_acyclic(&mut self, v1: usize, v2: usize, a: Label) -> Result<()> {
+        for v in [v1, v2] {
+            if v >= self.vertices.capacity()
+                || self
+                    .vertices
+                    .get(v)
+                    .is_none_or(|vtx| vtx.branch == BRANCH_NONE)
+            {
+                return Err(anyhow!("Can't bind ν{v1}.{a} to ν{v2}, ν{v} is absent"));
+            }
+        }
+        if let Some(path) = self.shortest_path(v2, v1) {
+            return Err(anyhow!(
+                "Can't bind ν{v1}.{a} to ν{v2}, it would close the cycle {}",
+                path.iter()
+                    .map(|v| format!("ν{v}"))
+                    .collect::<Vec<String>>()
+                    .join(" → ")
+            ));
+        }
+        self.bind(v1, v2, a);
+        Ok(())
+    }
+
+    /// Add a new object as an attribute of `parent`, in EO style,
+    /// and return its ID.
+    ///
+    /// The new vertex gets three edges at once: `parent` points to it
+    /// with the label `name`, while it points back to `parent` with
+    /// both `ρ` (its parent) and `σ` (its scope). For example:
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// let foo = g.add_object(0, Label::from_str("foo").unwrap());
+    /// assert_eq!(Some(foo), g.kid_str(0, "foo"));
+    /// assert_eq!(Some(0), g.kid(foo, Label::Greek('ρ')));
+    /// assert_eq!(Some(0), g.kid(foo, Label::Greek('σ')));
+    /// ```
+    ///
+    /// The ID is taken from [`Sodg::next_id`].
+    ///
+    /// # Panics
+    ///
+    /// If vertex `parent` is absent, it will panic.
+    pub fn add_object(&mut self, parent: usize, name: Label) -> usize {
+        let child = self.next_id();
+        self.add(child);
+        self.bind(parent, child, name);
+        self.bind(child, parent, Label::Greek('ρ'));
+        self.bind(child, parent, Label::Greek('σ'));
+        child
+    }
+
+    /// Remove an edge labeled as `a` from vertex `v1`.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(42);
+    /// g.bind(0, 42, Label::Alpha(0));
+    /// g.unbind(0, Label::Alpha(0));
+    /// assert!(g.kid(0, Label::Alpha(0)).is_none());
+    /// ```
+    ///
+    /// If there is no such edge, nothing happens. The vertices stay
+    /// in their branches.
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v1` is absent, it will panic.
+    #[inline]
+    pub fn unbind(&mut self, v1: usize, a: Label) {
+        let edges = &mut self.vertices.get_mut(v1).unwrap().edges;
+        if !edges.contains_key(&a) {
+            return;
+        }
+        edges.remove(&a);
+        self.forget_finds();
+        self.emit(|| Event::Unbound(v1, a));
+        #[cfg(debug_assertions)]
+        trace!("#unbind: edge ν{v1}.{a} removed");
+        self.tick();
+    }
+
+    /// Make an edge from vertex `v` to vertex `to`, labeled as the first
+    /// positional attribute not used yet, and return its index.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.add(2);
+    /// assert_eq!(0, g.push_arg(0, 1).unwrap());
+    /// assert_eq!(1, g.push_arg(0, 2).unwrap());
+    /// assert_eq!(2, g.kid(0, Label::Alpha(1)).unwrap());
+    /// ```
+    ///
+    /// The index is the smallest `i` such that there is no `αi` edge
+    /// yet, thus gaps are filled first.
+    ///
+    /// # Errors
+    ///
+    /// If vertex `v` already has `N` edges, an error will be returned.
+    ///
+    /// # Panics
+    ///
+    /// If either vertex `v` or `to` is absent, it will panic.
+    pub fn push_arg(&mut self, v: usize, to: usize) -> Result<usize> {
+        if self.vertices.get(v).unwrap().edges.len() == N {
+            return Err(anyhow!(
+                "Can't push an argument to ν{v}, it already has {N} edges"
+            ));
+        }
+        let i = (0..N)
+            .find(|i| self.kid(v, Label::Alpha(*i)).is_none())
+            .unwrap();
+        self.bind(v, to, Label::Alpha(i));
+        Ok(i)
+    }
+
+    /// Find a branch without members, growing the branches
+    /// twice if all of them are busy.
+    fn free_branch(&mut self) -> usize {
+        if let Some((b, _)) = self.branches.iter().find(|(_, m)| m.is_empty()) {
+            return b;
+        }
+        let b = self.branches.capacity();
+        self.grow_branches(b * 2);
+        b
+    }
+
+    /// Make room for `cap` branches, keeping the existing ones
+    /// together with their stores.
+    pub(crate) fn grow_branches(&mut self, cap: usize) {
+        if cap <= self.branches.capacity() {
+            return;
+        }
+        let mut branches = emap::Map::with_capacity_some(cap, microstack::Stack::new());
+        let mut stores = emap::Map::with_capacity_some(cap, 0);
+        for (b, members) in self.branches.iter() {
+            branches.insert(b, members.clone());
+        }
+        for (b, total) in self.stores.iter() {
+            stores.insert(b, *total);
+        }
+        self.branches = branches;
+        self.stores = stores;
+        #[cfg(debug_assertions)]
+        trace!("#grow_branches: there is room for {cap} branches now");
+    }
+
+    /// Move the store of vertex `v` from the branch it was in before,
+    /// to the branch it is in now, if it has data not taken yet.
+    fn move_store(&mut self, v: usize, before: usize) {
+        let vtx = self.vertices.get(v).unwrap();
+        if vtx.persistence == Persistence::Stored && vtx.branch != before {
+            *self.stores.get_mut(before).unwrap() -= 1;
+            *self.stores.get_mut(vtx.branch).unwrap() += 1;
+        }
+    }
+
+    /// Make an edge from vertex `v1` to vertex `v2`, parsing its label from a string.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Sodg;
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(42);
+    /// g.bind_str(0, 42, "forward").unwrap();
+    /// assert_eq!(42, g.kid_str(0, "forward").unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the label can't be parsed, an `Err` will be returned.
+    ///
+    /// # Panics
+    ///
+    /// If either vertex `v1` or `v2` is absent, it will panic.
+    #[inline]
+    pub fn bind_str(&mut self, v1: usize, v2: usize, a: &str) -> Result<()> {
+        let label = Label::from_str(a).with_context(|| format!("Can't bind ν{v1}.{a} to ν{v2}"))?;
+        self.bind(v1, v2, label);
+        Ok(())
+    }
+
+    /// Make an edge from vertex `v1` to vertex `v2`, with a label made
+    /// of a string, a char, an index, or a [`Label`] itself.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind_into(0, 1, "foo").unwrap();
+    /// g.bind_into(1, 0, 'ρ').unwrap();
+    /// g.bind_into(0, 1, 0).unwrap();
+    /// g.bind_into(0, 1, Label::Alpha(1)).unwrap();
+    /// assert_eq!(Some(1), g.kid_into(0, "α1").unwrap());
+    /// assert_eq!(Some(0), g.kid_into(1, 'ρ').unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the label can't be made, an `Err` will be returned.
+    ///
+    /// # Panics
+    ///
+    /// If either vertex `v1` or `v2` is absent, it will panic.
+    #[inline]
+    pub fn bind_into<A>(&mut self, v1: usize, v2: usize, a: A) -> Result<()>
+    where
+        A: TryInto<Label>,
+        A::Error: Into<anyhow::Error>,
+    {
+        let label = a
+            .try_into()
+            .map_err(Into::into)
+            .with_context(|| format!("Can't bind ν{v1} to ν{v2}"))?;
+        self.bind(v1, v2, label);
+        Ok(())
+    }
+
+    /// Make an edge from vertex `v1` to vertex `new`, but only if the edge
+    /// with this label currently leads to `expected`, or doesn't exist,
+    /// if `expected` is `None`.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.add(2);
+    /// assert!(g.cas_bind(0, Label::Alpha(0), None, 1).unwrap());
+    /// assert!(!g.cas_bind(0, Label::Alpha(0), None, 2).unwrap());
+    /// assert!(g.cas_bind(0, Label::Alpha(0), Some(1), 2).unwrap());
+    /// assert_eq!(Some(2), g.kid(0, Label::Alpha(0)));
+    /// ```
+    ///
+    /// Returns `true` if the edge was made, and `false` if the current
+    /// target of the edge is not the expected one.
+    ///
+    /// # Errors
+    ///
+    /// If either vertex `v1` or `new` is absent, an `Err` will be returned.
+    pub fn cas_bind(
+        &mut self,
+        v1: usize,
+        a: Label,
+        expected: Option<usize>,
+        new: usize,
+    ) -> Result<bool> {
+        for v in [v1, new] {
+            if v >= self.vertices.capacity()
+                || self
+                    .vertices
+                    .get(v)
+                    .is_none_or(|vtx| vtx.branch == BRANCH_NONE)
+            {
+                return Err(anyhow!("Can't bind ν{v1}.{a} to ν{new}, ν{v} is absent"));
+            }
+        }
+        if self.kid(v1, a) != expected {
+            return Ok(false);
+        }
+        self.bind(v1, new, a);
+        Ok(true)
+    }
+
+    /// Make an edge from vertex `v1` to vertex `v2`, just like [`Sodg::bind`]
+    /// does, and return the vertex the edge was leading to before.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.add(2);
+    /// assert_eq!(None, g.swap_edge(0, Label::Alpha(0), 1));
+    /// assert_eq!(Some(1), g.swap_edge(0, Label::Alpha(0), 2));
+    /// assert_eq!(Some(2), g.kid(0, Label::Alpha(0)));
+    /// ```
+    ///
+    /// If the edge didn't exist, `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// If either vertex `v1` or `v2` is absent, it will panic.
+    pub fn swap_edge(&mut self, v1: usize, a: Label, v2: usize) -> Option<usize> {
+        let before = self.kid(v1, a);
+        self.bind(v1, v2, a);
+        before
+    }
+
+    /// Remove redundant edges of vertex `v`, where both the label and
+    /// the target match an edge seen earlier, and return how many were removed.
+    ///
+    /// Two edges can't have exactly the same label, but they may have
+    /// different spellings of the same one: a [`Label::Str`] with the text
+    /// `ρ` means the same as the [`Label::Greek`] `ρ`, while the text `α1`
+    /// means the same as the [`Label::Alpha`] `α1`. Such edges may appear
+    /// after merging graphs made by different tools. For example:
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Greek('ρ'));
+    /// g.bind(0, 1, Label::from_str("\"ρ\"").unwrap());
+    /// assert_eq!(2, g.kids(0).count());
+    /// assert_eq!(1, g.dedup_edges(0));
+    /// assert_eq!(1, g.kids(0).count());
+    /// assert_eq!(Some(1), g.kid(0, Label::Greek('ρ')));
+    /// ```
+    ///
+    /// Of the duplicates, the edge with the normal form of the label is kept.
+    /// Edges with distinct labels pointing to the same target are not
+    /// duplicates and stay intact, as well as edges with the same label
+    /// pointing to different targets.
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v` is absent, it will panic.
+    pub fn dedup_edges(&mut self, v: usize) -> usize {
+        let vtx = self.vertices.get_mut(v).unwrap();
+        let mut seen: Vec<(Label, usize)> = vec![];
+        let mut removed = 0;
+        for (a, to) in &vtx.edges {
+            let norm = a.canonical();
+            if let Some(e) = seen
+                .iter_mut()
+                .find(|(b, t)| b.canonical() == norm && t == to)
+            {
+                removed += 1;
+                if *a == norm {
+                    e.0 = norm;
                 }
-                self.vertices.get_mut(v2).unwrap().branch = ours;
-                self.branches.get_mut(ours).unwrap().push(v2);
             } else {
-                vtx1.branch = theirs;
-                self.branches.get_mut(theirs).unwrap().push(v1);
+                seen.push((*a, *to));
             }
-        } else {
-            let vtx2 = self.vertices.get_mut(v2).unwrap();
-            if vtx2.branch == BRANCH_STATIC {
-                vtx2.branch = ours;
-                self.branches.get_mut(ours).unwrap().push(v2);
+        }
+        if removed > 0 {
+            vtx.edges.clear();
+            for (a, to) in seen {
+                vtx.edges.insert(a, to);
             }
+            self.forget_finds();
         }
         #[cfg(debug_assertions)]
-        trace!(
-            "#bind: edge added ν{}(b={}).{} → ν{}(b={})",
-            v1,
-            self.vertices.get(v1).unwrap().branch,
-            a,
-            v2,
-            self.vertices.get(v2).unwrap().branch,
-        );
+        trace!("#dedup_edges: {removed} duplicate edge(s) removed from ν{v}");
+        removed
+    }
+
+    /// Remove vertex `v` from the graph, together with its data, its edges,
+    /// and all edges pointing to it.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// g.remove(1);
+    /// assert_eq!(1, g.len());
+    /// assert!(g.kid(0, Label::Alpha(0)).is_none());
+    /// ```
+    ///
+    /// If vertex `v` is absent, nothing will happen.
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v` is out of the capacity of the graph, it will panic.
+    pub fn remove(&mut self, v: usize) {
+        self.erase(v);
+        self.tick();
+    }
+
+    /// Remove vertex `v` together with all edges leading to it,
+    /// without collecting garbage afterwards.
+    pub(crate) fn erase(&mut self, v: usize) {
+        self.erase_all(&[v]);
+    }
+
+    /// Remove all these vertices together with all edges leading to them,
+    /// without collecting garbage afterwards.
+    ///
+    /// The edges are removed in a single pass over all vertices, no matter
+    /// how many of them are removed. Absent vertices are ignored.
+    pub(crate) fn erase_all(&mut self, vx: &[usize]) {
+        let mut gone = HashSet::new();
+        for v in vx {
+            let vtx = self.vertices.get_mut(*v).unwrap();
+            let branch = vtx.branch;
+            if branch == BRANCH_NONE {
+                continue;
+            }
+            if vtx.persistence == Persistence::Stored {
+                *self.stores.get_mut(branch).unwrap() -= 1;
+            }
+            vtx.branch = BRANCH_NONE;
+            vtx.persistence = Persistence::Empty;
+            vtx.data = SharedHex::default();
+            vtx.edges.clear();
+            self.alive -= 1;
+            if branch > BRANCH_STATIC {
+                let members = self.branches.get_mut(branch).unwrap();
+                let rest: Vec<usize> = members.into_iter().filter(|m| m != v).collect();
+                *members = microstack::Stack::from_vec(rest);
+            }
+            gone.insert(*v);
+        }
+        if gone.is_empty() {
+            return;
+        }
+        self.forget_finds();
+        for (u, vtx) in self.vertices.iter_mut() {
+            if vtx.branch == BRANCH_NONE {
+                continue;
+            }
+            let cut: Vec<Label> = vtx
+                .edges
+                .iter()
+                .filter(|(_, to)| gone.contains(*to))
+                .map(|(a, _)| *a)
+                .collect();
+            for a in cut {
+                vtx.edges.remove(&a);
+                if let Some(cb) = self.listener {
+                    cb(&Event::Unbound(u, a));
+                }
+            }
+        }
+        #[cfg(debug_assertions)]
+        trace!("#remove: {} vertices removed", gone.len());
     }
 
     /// Set vertex data.
@@ -129,21 +730,195 @@ impl<const N: usize> Sodg<N> {
     ///
     /// # Panics
     ///
-    /// If vertex `v1` is absent, an `Err` will be returned.
+    /// If vertex `v1` is absent, it will panic.
     ///
-    /// If alerts trigger any error, the error will be returned here.
+    /// If alerts find any problems with the data, it will panic,
+    /// see [`Sodg::try_put`].
     #[inline]
     pub fn put(&mut self, v: usize, d: &Hex) {
+        self.put_shared(v, SharedHex::new(d.clone()));
+    }
+
+    /// Set vertex data from a slice of bytes.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// use sodg::Sodg;
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(42);
+    /// g.put_bytes(42, &[0xCA, 0xFE]);
+    /// assert_eq!("CA-FE", g.data(42).unwrap().print());
+    /// ```
+    ///
+    /// The [`Hex`] is made by [`Hex::from_slice`], thus short data
+    /// stays inline and doesn't allocate memory on the heap.
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v1` is absent, it will panic.
+    #[inline]
+    pub fn put_bytes(&mut self, v: usize, bytes: &[u8]) {
+        self.put_shared(v, SharedHex::new(Hex::from_slice(bytes)));
+    }
+
+    /// Set vertex data, which may be shared with other vertices.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, SharedHex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(1);
+    /// g.add(2);
+    /// let d = SharedHex::new(Hex::from_str_bytes("a large payload"));
+    /// g.put_shared(1, d.clone());
+    /// g.put_shared(2, d.clone());
+    /// assert_eq!(3, SharedHex::strong_count(&d));
+    /// ```
+    ///
+    /// The data is not copied, neither here nor in [`Sodg::data_shared`].
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v` is absent, it will panic.
+    ///
+    /// If alerts find any problems with the data, it will panic,
+    /// see [`Sodg::try_put`].
+    #[inline]
+    pub fn put_shared(&mut self, v: usize, d: SharedHex) {
+        if let Err(e) = self.store(v, d) {
+            panic!("{e:#}");
+        }
+    }
+
+    /// Set vertex data, unless alerts find any problems with it.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{alerts, Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.alert_on(alerts::no_nan_floats());
+    /// g.add(0);
+    /// g.try_put(0, &Hex::from(3.14)).unwrap();
+    /// assert!(g.try_put(0, &Hex::from(f64::NAN)).is_err());
+    /// assert_eq!(3.14, g.data(0).unwrap().to_f64().unwrap());
+    /// ```
+    ///
+    /// The alerts attached by [`Sodg::alert_on`] check the vertex right
+    /// after the data is set. If they find anything, the previous data
+    /// of the vertex is restored.
+    ///
+    /// # Errors
+    ///
+    /// If any alert finds problems, an error will be returned.
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v` is absent, it will panic.
+    pub fn try_put(&mut self, v: usize, d: &Hex) -> Result<()> {
+        self.store(v, SharedHex::new(d.clone()))
+    }
+
+    /// Set vertex data and check it with the alerts, if there are any.
+    fn store(&mut self, v: usize, d: SharedHex) -> Result<()> {
         let vtx = self.vertices.get_mut(v).unwrap();
+        let before = (vtx.persistence.clone(), vtx.data.clone());
+        if vtx.persistence != Persistence::Stored {
+            *self.stores.get_mut(vtx.branch).unwrap() += 1;
+        }
         vtx.persistence = Persistence::Stored;
-        vtx.data = d.clone();
-        *self.stores.get_mut(vtx.branch).unwrap() += 1;
+        vtx.data = d;
+        if !self.alerts.is_empty() {
+            if let Err(e) = self.validate(&[v]) {
+                let vtx = self.vertices.get_mut(v).unwrap();
+                if before.0 != Persistence::Stored {
+                    *self.stores.get_mut(vtx.branch).unwrap() -= 1;
+                }
+                (vtx.persistence, vtx.data) = before;
+                return Err(e.context(format!("Can't put data into ν{v}")));
+            }
+        }
+        self.emit(|| Event::Put(v));
+        self.verify();
         #[cfg(debug_assertions)]
-        trace!("#put: data of ν{v} set to {d}");
+        trace!(
+            "#put: data of ν{v} set to {}",
+            self.vertices.get(v).unwrap().data
+        );
+        Ok(())
+    }
+
+    /// Set vertex data, adding the vertex first if it is absent.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// use sodg::Sodg;
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.put_or_add(42, &Hex::from(7));
+    /// assert_eq!(1, g.len());
+    /// assert_eq!(7, g.data(42).unwrap().to_i64().unwrap());
+    /// ```
+    ///
+    /// If the vertex already exists, this is the same as [`Sodg::put`].
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v` is out of the capacity of the graph, it will panic.
+    #[inline]
+    pub fn put_or_add(&mut self, v: usize, d: &Hex) {
+        if self.vertices.get(v).unwrap().branch == BRANCH_NONE {
+            self.add(v);
+        }
+        self.put(v, d);
+    }
+
+    /// Append data to the data a vertex already has.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(42);
+    /// g.append(42, &Hex::from_str_bytes("hello, ")).unwrap();
+    /// g.append(42, &Hex::from_str_bytes("world!")).unwrap();
+    /// assert_eq!("hello, world!", g.data(42).unwrap().to_utf8().unwrap());
+    /// ```
+    ///
+    /// If the vertex has no data, this is the same as [`Sodg::put`].
+    /// The data becomes not taken, even if it was taken before.
+    ///
+    /// # Errors
+    ///
+    /// If vertex `v` is absent, an `Err` will be returned.
+    pub fn append(&mut self, v: usize, d: &Hex) -> Result<()> {
+        if v >= self.vertices.capacity()
+            || self
+                .vertices
+                .get(v)
+                .is_none_or(|vtx| vtx.branch == BRANCH_NONE)
+        {
+            return Err(anyhow!("Can't append data to ν{v}, the vertex is absent"));
+        }
+        let joined = self
+            .data_ref(v)
+            .map_or_else(|| d.clone(), |before| before.concat(d));
+        self.put_shared(v, SharedHex::new(joined));
+        Ok(())
     }
 
     /// Read vertex data, and then submit the vertex to garbage collection.
     ///
+    /// This is an alias of [`Sodg::take`]. There are three ways to read data:
+    /// [`Sodg::take`] consumes it (and may destroy the branch of the vertex),
+    /// [`Sodg::peek`] makes a copy without changing anything, and
+    /// [`Sodg::data_ref`] borrows it, also without changing anything.
+    ///
     /// For example:
     ///
     /// ```
@@ -170,6 +945,97 @@ impl<const N: usize> Sodg<N> {
     /// If vertex `v1` is absent, it will panic.
     #[inline]
     pub fn data(&mut self, v: usize) -> Option<Hex> {
+        self.take(v)
+    }
+
+    /// Read vertex data, marking it as taken, just like [`Sodg::data`],
+    /// but without panicking if the vertex is absent.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(42);
+    /// assert!(g.try_data(42).unwrap().is_none());
+    /// g.put(42, &Hex::from(7));
+    /// assert_eq!(7, g.try_data(42).unwrap().unwrap().to_i64().unwrap());
+    /// assert!(g.try_data(43).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If vertex `v` is absent, an `Err` will be returned. If it exists,
+    /// but doesn't have any data, `Ok(None)` will be returned.
+    #[inline]
+    pub fn try_data(&mut self, v: usize) -> Result<Option<Hex>> {
+        if v >= self.vertices.capacity()
+            || self
+                .vertices
+                .get(v)
+                .is_none_or(|vtx| vtx.branch == BRANCH_NONE)
+        {
+            return Err(anyhow!("Can't read data of ν{v}, the vertex is absent"));
+        }
+        Ok(self.take(v))
+    }
+
+    /// Read vertex data, marking it as taken, and then submit the vertex
+    /// to garbage collection.
+    ///
+    /// When the last data of a branch is taken, all vertices
+    /// of the branch are destroyed, unless it is the static branch,
+    /// which is never destroyed. For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(1);
+    /// g.add(2);
+    /// g.bind(1, 2, Label::Alpha(0));
+    /// g.put(2, &Hex::from(42));
+    /// assert_eq!(42, g.take(2).unwrap().to_i64().unwrap());
+    /// assert_eq!(0, g.len());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v1` is absent, it will panic.
+    #[inline]
+    pub fn take(&mut self, v: usize) -> Option<Hex> {
+        self.data_shared(v).map(|d| Hex::clone(&d))
+    }
+
+    /// Read vertex data, marking it as taken, without copying it.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, SharedHex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(42);
+    /// let d = SharedHex::new(Hex::from(7));
+    /// g.put_shared(42, d.clone());
+    /// let taken = g.data_shared(42).unwrap();
+    /// assert!(SharedHex::ptr_eq(&d, &taken));
+    /// ```
+    ///
+    /// It works exactly like [`Sodg::take`], including the destruction
+    /// of the branch, when its last data is taken.
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v` is absent, it will panic.
+    #[inline]
+    pub fn data_shared(&mut self, v: usize) -> Option<SharedHex> {
+        if self.vertices.get(v).unwrap().persistence == Persistence::Empty {
+            if let Some(f) = self.lambdas.get(&v) {
+                let d = f(self, v);
+                self.put(v, &d);
+                #[cfg(debug_assertions)]
+                trace!("#data_shared: data of ν{v} produced by its lambda");
+            }
+        }
         let vtx = self.vertices.get_mut(v).unwrap();
         match vtx.persistence {
             Persistence::Stored => {
@@ -178,37 +1044,255 @@ impl<const N: usize> Sodg<N> {
                 let branch = vtx.branch;
                 let s = self.stores.get_mut(branch).unwrap();
                 *s -= 1;
-                if *s == 0 {
-                    let members = self.branches.get_mut(branch).unwrap();
-                    for v in members.into_iter() {
-                        self.vertices.get_mut(v).unwrap().branch = BRANCH_NONE;
+                let mut members = vec![];
+                if *s == 0 && branch > BRANCH_STATIC {
+                    members = self
+                        .branches
+                        .get(branch)
+                        .unwrap()
+                        .into_iter()
+                        .filter(|m| {
+                            self.vertices
+                                .get(*m)
+                                .is_some_and(|vtx| vtx.branch == branch)
+                        })
+                        .collect();
+                    for v in &members {
+                        self.vertices.get_mut(*v).unwrap().branch = BRANCH_NONE;
                     }
+                    self.alive -= members.len();
                     #[cfg(debug_assertions)]
                     trace!(
-                        "#data: branch no.{} destroyed {} vertices as garbage: {}",
+                        "#data_shared: branch no.{} destroyed {} vertices as garbage: {}",
                         branch,
                         members.len(),
                         members
-                            .into_iter()
+                            .iter()
                             .map(|v| format!("ν{v}"))
                             .collect::<Vec<String>>()
                             .join(", ")
                     );
-                    members.clear();
+                    self.branches.get_mut(branch).unwrap().clear();
+                    self.forget_finds();
+                }
+                self.emit(|| Event::Took(v));
+                if !members.is_empty() {
+                    self.emit(|| Event::Collected(members));
                 }
+                self.verify();
                 #[cfg(debug_assertions)]
-                trace!("#data: data of ν{v} retrieved");
+                trace!("#data_shared: data of ν{v} retrieved");
+                self.tick();
                 Some(d)
             }
             Persistence::Taken => {
                 #[cfg(debug_assertions)]
-                trace!("#data: data of ν{v} retrieved again");
+                trace!("#data_shared: data of ν{v} retrieved again");
                 Some(vtx.data.clone())
             }
             Persistence::Empty => None,
         }
     }
 
+    /// Read vertex data without taking it, making a copy.
+    ///
+    /// Neither the persistence of the vertex nor its branch are affected,
+    /// for example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(42);
+    /// g.put(42, &Hex::from(7));
+    /// assert_eq!(7, g.peek(42).unwrap().to_i64().unwrap());
+    /// assert_eq!(7, g.peek(42).unwrap().to_i64().unwrap());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v1` is absent, it will panic.
+    #[must_use]
+    #[inline]
+    pub fn peek(&self, v: usize) -> Option<Hex> {
+        self.data_ref(v)
+            .cloned()
+            .or_else(|| self.lambdas.get(&v).map(|f| f(self, v)))
+    }
+
+    /// Register a producer of data for the vertex, which is called
+    /// when the data is read, but the vertex doesn't have any.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(42);
+    /// g.set_lambda(42, |_, v| Hex::from(v as i64 * 2));
+    /// assert_eq!(84, g.peek(42).unwrap().to_i64().unwrap());
+    /// assert_eq!(84, g.data(42).unwrap().to_i64().unwrap());
+    /// ```
+    ///
+    /// The data produced by [`Sodg::data`] is kept in the vertex, just like
+    /// if it was put there by [`Sodg::put`], so the producer is not called
+    /// again. [`Sodg::peek`] can't modify the graph, that's why it calls
+    /// the producer every time. Producers are not saved by [`Sodg::save`].
+    pub fn set_lambda(&mut self, v: usize, f: Lambda<N>) {
+        self.lambdas.insert(v, f);
+    }
+
+    /// Borrow vertex data, without taking it.
+    ///
+    /// Neither the persistence of the vertex nor its branch are affected,
+    /// for example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(42);
+    /// g.put(42, &Hex::from(7));
+    /// assert_eq!(8, g.data_ref(42).unwrap().len());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v1` is absent, it will panic.
+    #[must_use]
+    #[inline]
+    pub fn data_ref(&self, v: usize) -> Option<&Hex> {
+        let vtx = self.vertices.get(v).unwrap();
+        if vtx.persistence == Persistence::Empty {
+            None
+        } else {
+            Some(&vtx.data)
+        }
+    }
+
+    /// Get the length of vertex data in bytes, without copying it.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(42);
+    /// assert!(g.data_len(42).is_none());
+    /// g.put(42, &Hex::from_str_bytes("hello"));
+    /// assert_eq!(Some(5), g.data_len(42));
+    /// ```
+    ///
+    /// If the vertex is absent or doesn't have any data, `None` is returned.
+    /// Neither the persistence of the vertex nor its branch are affected.
+    #[must_use]
+    #[inline]
+    pub fn data_len(&self, v: usize) -> Option<usize> {
+        if v >= self.vertices.capacity() {
+            return None;
+        }
+        self.vertices
+            .get(v)
+            .filter(|vtx| vtx.branch != BRANCH_NONE && vtx.persistence != Persistence::Empty)
+            .map(|vtx| vtx.data.len())
+    }
+
+    /// Read vertex data as `i64`, without taking it.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(42);
+    /// g.put(42, &Hex::from(7));
+    /// assert_eq!(7, g.read_i64(42).unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the vertex has no data, or it is not an `i64`, an `Err` will be returned.
+    pub fn read_i64(&self, v: usize) -> Result<i64> {
+        self.peeked(v)?
+            .to_i64()
+            .with_context(|| format!("Can't read ν{v} as i64"))
+    }
+
+    /// Read vertex data as `f64`, without taking it.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(42);
+    /// g.put(42, &Hex::from(2.5));
+    /// assert_eq!(2.5, g.read_f64(42).unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the vertex has no data, or it is not an `f64`, an `Err` will be returned.
+    pub fn read_f64(&self, v: usize) -> Result<f64> {
+        self.peeked(v)?
+            .to_f64()
+            .with_context(|| format!("Can't read ν{v} as f64"))
+    }
+
+    /// Read vertex data as a UTF-8 string, without taking it.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(42);
+    /// g.put(42, &Hex::from_str_bytes("hello"));
+    /// assert_eq!("hello", g.read_utf8(42).unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the vertex has no data, or it is not a valid UTF-8 string,
+    /// an `Err` will be returned.
+    pub fn read_utf8(&self, v: usize) -> Result<String> {
+        self.peeked(v)?
+            .to_utf8()
+            .with_context(|| format!("Can't read ν{v} as UTF-8"))
+    }
+
+    /// Read vertex data as `bool`, without taking it.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(42);
+    /// g.put(42, &Hex::from(true));
+    /// assert!(g.read_bool(42).unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the vertex has no data, an `Err` will be returned.
+    pub fn read_bool(&self, v: usize) -> Result<bool> {
+        Ok(self.peeked(v)?.to_bool())
+    }
+
+    /// Read vertex data, just like [`Sodg::peek`] does, but
+    /// complain if there is no data.
+    fn peeked(&self, v: usize) -> Result<Hex> {
+        if v >= self.vertices.capacity()
+            || self
+                .vertices
+                .get(v)
+                .is_none_or(|vtx| vtx.branch == BRANCH_NONE)
+        {
+            return Err(anyhow!("Can't read data of ν{v}, the vertex is absent"));
+        }
+        self.peek(v)
+            .with_context(|| format!("Can't read data of ν{v}, it is empty"))
+    }
+
     /// Find all kids of a vertex.
     ///
     /// For example:
@@ -255,6 +1339,56 @@ impl<const N: usize> Sodg<N> {
             .iter()
     }
 
+    /// Find all kids of a vertex and return their copies, letting
+    /// the graph be modified while they are iterated.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// for (a, to) in g.kids_owned(0) {
+    ///     g.add(2);
+    ///     g.bind(to, 2, a);
+    /// }
+    /// assert_eq!(2, g.kid(1, Label::Alpha(0)).unwrap());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v` is absent, it will panic.
+    #[must_use]
+    pub fn kids_owned(&self, v: usize) -> Vec<(Label, usize)> {
+        self.kids(v).map(|(a, to)| (*a, *to)).collect()
+    }
+
+    /// Find all kids of a vertex, skipping the system edges,
+    /// see [`Label::is_system`].
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(1, 0, Label::Greek('ρ'));
+    /// g.bind(1, 0, Label::from_str("foo").unwrap());
+    /// assert_eq!(1, g.user_kids(1).count());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v` is absent, it will panic.
+    #[inline]
+    pub fn user_kids(&self, v: usize) -> impl Iterator<Item = (&Label, &usize)> + '_ {
+        self.kids(v).filter(|(a, _)| !a.is_system())
+    }
+
     /// Find a kid of a vertex, by its edge name, and return the ID of the vertex found.
     ///
     /// For example:
@@ -283,10 +1417,82 @@ impl<const N: usize> Sodg<N> {
         }
         None
     }
-}
 
-#[cfg(test)]
-use std::str::FromStr;
+    /// Find the targets of the positional edges of a vertex, which are
+    /// `α0`, `α1`, `α2`, and so on, in the order of their indexes.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.add(2);
+    /// g.bind(0, 2, Label::Alpha(1));
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// assert_eq!(vec![1, 2], g.args(0));
+    /// ```
+    ///
+    /// The search stops at the first missing index, thus if there is
+    /// `α2`, but no `α1`, only the target of `α0` is returned.
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v` is absent, it will panic.
+    #[must_use]
+    pub fn args(&self, v: usize) -> Vec<usize> {
+        (0..).map_while(|i| self.kid(v, Label::Alpha(i))).collect()
+    }
+
+    /// Find a kid of a vertex, by its edge name given as a string.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(42);
+    /// g.bind(0, 42, Label::Alpha(3));
+    /// assert_eq!(42, g.kid_str(0, "α3").unwrap());
+    /// assert!(g.kid_str(0, "α4").is_none());
+    /// ```
+    ///
+    /// If the label can't be parsed, `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v1` is absent, it will panic.
+    #[must_use]
+    #[inline]
+    pub fn kid_str(&self, v: usize, a: &str) -> Option<usize> {
+        Label::from_str(a).ok().and_then(|label| self.kid(v, label))
+    }
+
+    /// Find a kid of a vertex, by its edge name made of a string, a char,
+    /// an index, or a [`Label`] itself, see [`Sodg::bind_into`].
+    ///
+    /// # Errors
+    ///
+    /// If the label can't be made, an `Err` will be returned.
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v` is absent, it will panic.
+    #[inline]
+    pub fn kid_into<A>(&self, v: usize, a: A) -> Result<Option<usize>>
+    where
+        A: TryInto<Label>,
+        A::Error: Into<anyhow::Error>,
+    {
+        let label = a
+            .try_into()
+            .map_err(Into::into)
+            .with_context(|| format!("Can't find a kid of ν{v}"))?;
+        Ok(self.kid(v, label))
+    }
+}
 
 #[test]
 fn adds_simple_vertex() {
@@ -328,6 +1534,26 @@ fn fetches_kid() {
     assert_eq!(2, g.kid(1, k).unwrap());
 }
 
+#[test]
+fn fetches_kid_by_string() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(1);
+    g.add(2);
+    g.bind_str(1, 2, "hello").unwrap();
+    assert_eq!(2, g.kid_str(1, "hello").unwrap());
+    assert_eq!(2, g.kid(1, Label::from_str("hello").unwrap()).unwrap());
+    assert!(g.kid_str(1, "world").is_none());
+}
+
+#[test]
+fn refuses_to_bind_broken_label() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(1);
+    g.add(2);
+    assert!(g.bind_str(1, 2, "too-long-label").is_err());
+    assert!(g.kids(1).next().is_none());
+}
+
 #[test]
 fn binds_two_names() {
     let mut g: Sodg<16> = Sodg::empty(256);
@@ -441,3 +1667,426 @@ fn adds_twice() {
     g.add(0);
     g.add(0);
 }
+
+#[test]
+fn dedups_edges() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(1);
+    g.add(2);
+    g.add(3);
+    g.bind(1, 2, Label::from_str("a").unwrap());
+    g.bind(1, 2, Label::from_str("b").unwrap());
+    g.bind(1, 3, Label::Greek('ρ'));
+    g.bind(1, 3, Label::Alpha(1));
+    let vtx = g.vertices.get_mut(1).unwrap();
+    vtx.edges.insert(Label::from_str("\"ρ\"").unwrap(), 3);
+    vtx.edges.insert(Label::from_str("\"α1\"").unwrap(), 3);
+    vtx.edges.insert(Label::from_str("\"σ\"").unwrap(), 3);
+    vtx.edges.insert(Label::Greek('σ'), 2);
+    assert_eq!(8, g.kids(1).count());
+    assert_eq!(2, g.dedup_edges(1));
+    assert_eq!(6, g.kids(1).count());
+    assert_eq!(Some(3), g.kid(1, Label::Greek('ρ')));
+    assert_eq!(Some(3), g.kid(1, Label::Alpha(1)));
+    assert!(g.kid(1, Label::from_str("\"ρ\"").unwrap()).is_none());
+    assert_eq!(Some(2), g.kid(1, Label::from_str("a").unwrap()));
+    assert_eq!(Some(2), g.kid(1, Label::from_str("b").unwrap()));
+    assert_eq!(0, g.dedup_edges(1));
+}
+
+#[test]
+fn puts_into_absent_vertex() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    assert_eq!(1, g.len());
+    g.put_or_add(5, &Hex::from(42));
+    assert_eq!(2, g.len());
+    assert_eq!(42, g.data(5).unwrap().to_i64().unwrap());
+}
+
+#[test]
+fn removes_vertex() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(1);
+    g.add(2);
+    g.bind(1, 2, Label::Alpha(0));
+    g.bind(2, 1, Label::Alpha(0));
+    g.put(2, &Hex::from(42));
+    g.remove(2);
+    assert_eq!(1, g.len());
+    assert!(g.kid(1, Label::Alpha(0)).is_none());
+    assert_eq!(&0, g.stores.get(2).unwrap());
+    assert_eq!(1, g.branches.get(2).unwrap().len());
+    g.add(2);
+    assert!(g.data(2).is_none());
+    assert_eq!(0, g.kids(2).count());
+}
+
+#[test]
+fn reads_data_in_three_ways() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(1);
+    g.add(2);
+    g.bind(1, 2, Label::Alpha(0));
+    g.add(3);
+    g.bind(1, 3, Label::Alpha(1));
+    g.put(2, &Hex::from(2));
+    g.put(3, &Hex::from(3));
+    assert_eq!(&2, g.stores.get(2).unwrap());
+    assert_eq!(2, g.peek(2).unwrap().to_i64().unwrap());
+    assert_eq!(2, g.data_ref(2).unwrap().to_i64().unwrap());
+    assert!(g.vertices.get(2).unwrap().persistence == Persistence::Stored);
+    assert_eq!(&2, g.stores.get(2).unwrap());
+    assert_eq!(2, g.take(2).unwrap().to_i64().unwrap());
+    assert!(g.vertices.get(2).unwrap().persistence == Persistence::Taken);
+    assert_eq!(&1, g.stores.get(2).unwrap());
+    assert_eq!(2, g.peek(2).unwrap().to_i64().unwrap());
+    assert_eq!(3, g.len());
+    assert_eq!(3, g.take(3).unwrap().to_i64().unwrap());
+    assert_eq!(&0, g.stores.get(2).unwrap());
+    assert_eq!(0, g.len());
+    assert!(g.peek(1).is_none());
+    assert!(g.data_ref(1).is_none());
+}
+
+#[test]
+fn adds_in_branch() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add_in_branch(1, 4).unwrap();
+    g.add_in_branch(2, 4).unwrap();
+    g.put(2, &Hex::from(42));
+    assert_eq!(vec![1, 2], g.branch_members(4));
+    assert_eq!(&1, g.stores.get(4).unwrap());
+    g.add_in_branch(2, 5).unwrap();
+    assert_eq!(vec![1], g.branch_members(4));
+    assert_eq!(vec![2], g.branch_members(5));
+    assert_eq!(&0, g.stores.get(4).unwrap());
+    assert_eq!(&1, g.stores.get(5).unwrap());
+    assert!(g.add_in_branch(3, 0).is_err());
+    assert!(g.add_in_branch(3, 100).is_err());
+    g.data(2);
+    assert_eq!(1, g.len());
+}
+
+#[test]
+fn puts_bytes() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(1);
+    g.add(2);
+    let bytes = b"Hello, world!";
+    g.put_bytes(1, bytes);
+    g.put(2, &Hex::from_slice(bytes));
+    assert_eq!(g.data(2).unwrap(), g.data(1).unwrap());
+}
+
+#[test]
+fn reads_data_of_absent_vertex() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    assert!(g.try_data(42).is_err());
+    assert!(g.try_data(1024).is_err());
+    g.add(42);
+    assert!(g.try_data(42).unwrap().is_none());
+    g.put(42, &Hex::from(7));
+    assert_eq!(Hex::from(7), g.try_data(42).unwrap().unwrap());
+}
+
+#[test]
+fn reads_args_in_order() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    for v in 1..5 {
+        g.add(v);
+    }
+    g.bind(0, 3, Label::Alpha(2));
+    g.bind(0, 1, Label::Alpha(0));
+    g.bind(0, 2, Label::Alpha(1));
+    g.bind(0, 4, Label::Alpha(4));
+    g.bind(0, 4, Label::from_str("foo").unwrap());
+    assert_eq!(vec![1, 2, 3], g.args(0));
+    assert!(g.args(1).is_empty());
+}
+
+#[test]
+fn pushes_args() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    for v in 1..5 {
+        g.add(v);
+    }
+    assert_eq!(0, g.push_arg(0, 1).unwrap());
+    assert_eq!(1, g.push_arg(0, 2).unwrap());
+    assert_eq!(2, g.push_arg(0, 3).unwrap());
+    assert_eq!(vec![1, 2, 3], g.args(0));
+    g.unbind(0, Label::Alpha(1));
+    assert_eq!(vec![1], g.args(0));
+    assert_eq!(1, g.push_arg(0, 4).unwrap());
+    assert_eq!(vec![1, 4, 3], g.args(0));
+}
+
+#[test]
+fn refuses_to_push_arg_into_full_vertex() {
+    let mut g: Sodg<2> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    assert_eq!(0, g.push_arg(0, 1).unwrap());
+    assert_eq!(1, g.push_arg(0, 1).unwrap());
+    assert!(g.push_arg(0, 1).is_err());
+    assert_eq!(vec![1, 1], g.args(0));
+}
+
+#[test]
+fn binds_while_iterating_kids() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.add(2);
+    g.bind(0, 1, Label::Alpha(0));
+    g.bind(0, 2, Label::Alpha(1));
+    for (a, to) in g.kids_owned(0) {
+        let id = g.next_id();
+        g.add(id);
+        g.bind(to, id, a);
+        g.bind(0, id, Label::from_str(format!("x{to}").as_str()).unwrap());
+    }
+    assert_eq!(4, g.kids(0).count());
+    assert!(g.kid(1, Label::Alpha(0)).is_some());
+    assert!(g.kid(2, Label::Alpha(1)).is_some());
+}
+
+#[test]
+fn shares_data() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    let d = SharedHex::new(Hex::from_str_bytes("Hello, world!"));
+    for v in 1..4 {
+        g.add(v);
+        g.put_shared(v, d.clone());
+    }
+    assert_eq!(4, SharedHex::strong_count(&d));
+    let taken = g.data_shared(2).unwrap();
+    assert!(SharedHex::ptr_eq(&d, &taken));
+    assert_eq!(5, SharedHex::strong_count(&d));
+    assert_eq!(*d, g.data(3).unwrap());
+    g.put(1, &Hex::from(42));
+    assert_eq!(4, SharedHex::strong_count(&d));
+}
+
+#[test]
+fn produces_data_lazily() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.set_lambda(0, |_, _| Hex::from(7));
+    assert_eq!(Some(Hex::from(7)), g.peek(0));
+    assert!(g.data_ref(0).is_none());
+    assert_eq!(Some(Hex::from(7)), g.data(0));
+    assert_eq!(Some(&Hex::from(7)), g.data_ref(0));
+    g.set_lambda(0, |_, _| Hex::from(8));
+    assert_eq!(Some(Hex::from(7)), g.data(0));
+    g.add(1);
+    assert!(g.data(1).is_none());
+}
+
+#[test]
+fn binds_with_any_label() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind_into(0, 1, "foo").unwrap();
+    g.bind_into(0, 1, 'π').unwrap();
+    g.bind_into(0, 1, 3).unwrap();
+    g.bind_into(0, 1, Label::Greek('σ')).unwrap();
+    assert_eq!(Some(1), g.kid(0, Label::from_str("foo").unwrap()));
+    assert_eq!(Some(1), g.kid(0, Label::Greek('π')));
+    assert_eq!(Some(1), g.kid(0, Label::Alpha(3)));
+    assert_eq!(Some(1), g.kid_into(0, 'σ').unwrap());
+    assert!(g.bind_into(0, 1, "too-long-label").is_err());
+    assert!(g.bind_into(0, 1, ' ').is_err());
+    assert!(g.kid_into(0, "αx").is_err());
+    assert_eq!(4, g.kids(0).count());
+}
+
+#[test]
+fn binds_with_compare_and_swap() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.add(2);
+    let a = Label::Greek('φ');
+    assert!(g.cas_bind(0, a, None, 1).unwrap());
+    assert_eq!(Some(1), g.kid(0, a));
+    assert!(!g.cas_bind(0, a, Some(2), 2).unwrap());
+    assert!(!g.cas_bind(0, a, None, 2).unwrap());
+    assert_eq!(Some(1), g.kid(0, a));
+    assert!(g.cas_bind(0, a, Some(1), 2).unwrap());
+    assert_eq!(Some(2), g.kid(0, a));
+    assert!(g.cas_bind(0, a, Some(2), 3).is_err());
+    assert!(g.cas_bind(5, a, None, 1).is_err());
+}
+
+#[test]
+fn skips_system_kids() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.add(2);
+    g.bind(1, 0, Label::Greek('ρ'));
+    g.bind(1, 2, Label::from_str("foo").unwrap());
+    let kids: Vec<(Label, usize)> = g.user_kids(1).map(|(a, v)| (*a, *v)).collect();
+    assert_eq!(vec![(Label::from_str("foo").unwrap(), 2)], kids);
+    assert_eq!(2, g.kids(1).count());
+}
+
+#[test]
+fn measures_data_without_copying() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.put(1, &Hex::from_str_bytes("hello, world!"));
+    assert_eq!(g.peek(1).unwrap().len(), g.data_len(1).unwrap());
+    assert_eq!(None, g.data_len(0));
+    assert_eq!(None, g.data_len(2));
+    assert_eq!(None, g.data_len(1000));
+}
+
+#[test]
+fn swaps_edge_returning_previous_target() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.add(2);
+    let a = Label::from_str("foo").unwrap();
+    assert_eq!(None, g.swap_edge(0, a, 1));
+    assert_eq!(Some(1), g.swap_edge(0, a, 2));
+    assert_eq!(Some(2), g.swap_edge(0, a, 2));
+    assert_eq!(Some(2), g.kid(0, a));
+    assert_eq!(1, g.kids(0).count());
+}
+
+#[test]
+fn binds_to_absent_vertices() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    let branch = g.vertices.get(1).unwrap().branch;
+    g.bind_auto(1, 7, Label::Alpha(0));
+    g.bind_auto(8, 9, Label::from_str("foo").unwrap());
+    assert_eq!(5, g.len());
+    assert_eq!(Some(7), g.kid(1, Label::Alpha(0)));
+    assert_eq!(Some(9), g.kid_str(8, "foo"));
+    assert_eq!(branch, g.vertices.get(1).unwrap().branch);
+    assert!(g.check_invariants().is_ok());
+}
+
+#[test]
+fn appends_data() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    g.append(1, &Hex::from_slice(&[0xDE, 0xAD])).unwrap();
+    g.append(1, &Hex::from_slice(&[0xBE, 0xEF])).unwrap();
+    assert!(g.check_invariants().is_ok());
+    assert_eq!("DE-AD-BE-EF", g.peek(1).unwrap().print());
+    g.append(1, &Hex::empty()).unwrap();
+    assert_eq!("DE-AD-BE-EF", g.data(1).unwrap().print());
+    assert!(g.append(7, &Hex::from(1)).is_err());
+}
+
+#[test]
+fn adds_eo_object() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    let foo = g.add_object(0, Label::from_str("foo").unwrap());
+    let bar = g.add_object(foo, Label::Alpha(0));
+    assert_eq!(3, g.len());
+    assert_eq!(Some(foo), g.kid_str(0, "foo"));
+    assert_eq!(Some(bar), g.kid(foo, Label::Alpha(0)));
+    assert_eq!(Some(foo), g.kid(bar, Label::Greek('ρ')));
+    assert_eq!(Some(foo), g.kid(bar, Label::Greek('σ')));
+    assert_eq!(Some(0), g.kid(foo, Label::Greek('ρ')));
+    assert_eq!(Some(0), g.kid(foo, Label::Greek('σ')));
+    assert!(g.check_invariants().is_ok());
+}
+
+#[test]
+fn refuses_to_close_cycle() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    for v in 0..3 {
+        g.add(v);
+    }
+    g.bind_acyclic(0, 1, Label::Alpha(0)).unwrap();
+    g.bind_acyclic(1, 2, Label::Alpha(0)).unwrap();
+    g.bind_acyclic(0, 2, Label::Alpha(1)).unwrap();
+    let before = g.to_xml_full().unwrap();
+    let msg = g
+        .bind_acyclic(2, 0, Label::Greek('ρ'))
+        .unwrap_err()
+        .to_string();
+    assert!(msg.contains("ν0 → ν2"), "{msg}");
+    assert!(g.bind_acyclic(1, 1, Label::Alpha(1)).is_err());
+    assert!(g.bind_acyclic(0, 7, Label::Alpha(2)).is_err());
+    assert_eq!(before, g.to_xml_full().unwrap());
+}
+
+#[test]
+fn reads_typed_data() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    for v in 0..5 {
+        g.add(v);
+    }
+    g.put(0, &Hex::from(-42));
+    g.put(1, &Hex::from(3.25));
+    g.put(2, &Hex::from_str_bytes("привет"));
+    g.put(3, &Hex::from(true));
+    assert_eq!(-42, g.read_i64(0).unwrap());
+    assert!((g.read_f64(1).unwrap() - 3.25).abs() < f64::EPSILON);
+    assert_eq!("привет", g.read_utf8(2).unwrap());
+    assert!(g.read_bool(3).unwrap());
+    assert_eq!(-42, g.read_i64(0).unwrap());
+    assert!(g.read_i64(2).is_err());
+    assert!(g.read_utf8(4).is_err());
+    assert!(g.read_bool(9).is_err());
+}
+
+#[test]
+fn assigns_same_branches_on_bind() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    for v in 0..6 {
+        g.add(v);
+    }
+    g.bind(0, 1, Label::Alpha(0));
+    g.bind(1, 2, Label::Alpha(0));
+    g.bind(3, 2, Label::Alpha(0));
+    g.bind(4, 4, Label::Alpha(0));
+    g.put(5, &Hex::from(42));
+    g.bind(2, 5, Label::Alpha(1));
+    let branch = |v: usize| g.vertices.get(v).unwrap().branch;
+    assert_eq!(2, branch(0));
+    assert_eq!(2, branch(1));
+    assert_eq!(2, branch(2));
+    assert_eq!(2, branch(3));
+    assert_eq!(3, branch(4));
+    assert_eq!(2, branch(5));
+    assert_eq!(vec![0, 1, 2, 3, 5], g.branch_members(2));
+    assert_eq!(1, *g.stores.get(2).unwrap());
+    assert_eq!(0, *g.stores.get(BRANCH_STATIC).unwrap());
+}
+
+#[test]
+fn destroys_branches_of_re_added_vertex() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(1);
+    g.add(2);
+    g.bind(1, 2, Label::Alpha(0));
+    g.add(2);
+    g.add(3);
+    g.bind(3, 2, Label::Alpha(0));
+    g.put(1, &Hex::from(42));
+    g.put(3, &Hex::from(7));
+    g.check_invariants().unwrap();
+    g.data(1);
+    assert_eq!(2, g.len());
+    g.data(3);
+    assert_eq!(0, g.len());
+    g.check_invariants().unwrap();
+}
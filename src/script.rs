@@ -38,6 +38,20 @@ impl Script {
     /// 2) a variable started with `$`, 3) an attribute name, or
     /// 4) data in `XX-XX-...` hexadecimal format.
     ///
+    /// The grammar is strictly positional:
+    ///
+    /// ```text
+    /// ADD(<vertex>);
+    /// ADD(<vertex>, <data>);
+    /// BIND(<vertex>, <vertex>, <label>);
+    /// PUT(<vertex>, <data>);
+    /// ```
+    ///
+    /// Here, `<vertex>` is either `42`, `ν42`, or `$ν42` (a variable), while
+    /// `<label>` is always parsed as a [`Label`], never as a vertex, even if
+    /// it looks like one: `BIND(0, 1, ν2)` makes an edge labeled as `ν2`.
+    /// The `ADD(5, DE-AD)` is a shorter form of `ADD(5); PUT(5, DE-AD)`.
+    ///
     /// For example:
     ///
     /// ```
@@ -112,7 +126,11 @@ impl Script {
         match &cap[1] {
             "ADD" => {
                 let v = self.parse(args.first().with_context(|| "V is expected")?, g)?;
+                let d = args.get(1).map(|a| Self::parse_data(a)).transpose()?;
                 g.add(v);
+                if let Some(d) = d {
+                    g.put(v, &d);
+                }
             }
             "BIND" => {
                 let v1 = self.parse(args.first().with_context(|| "V1 is expected")?, g)?;
@@ -145,16 +163,32 @@ impl Script {
         }
         let d: &str = &DATA_STRIP.replace_all(s, "");
         if DATA.is_match(d) {
-            let bytes: Vec<u8> = (0..d.len())
-                .step_by(2)
-                .map(|i| u8::from_str_radix(&d[i..i + 2], 16).unwrap())
-                .collect();
+            let bytes = Self::parse_bytes(d).with_context(|| format!("Can't parse data '{s}'"))?;
             Ok(Hex::from_vec(bytes))
         } else {
             Err(anyhow!("Can't parse data '{s}'"))
         }
     }
 
+    /// Parse pairs of hex digits, like `DEAD`, into bytes.
+    ///
+    /// # Errors
+    ///
+    /// If any pair is not a hex number, an error will be returned,
+    /// even though the regex of [`Script::parse_data`] must not let
+    /// such pairs through.
+    fn parse_bytes(d: &str) -> Result<Vec<u8>> {
+        (0..d.len())
+            .step_by(2)
+            .map(|i| {
+                let pair = d
+                    .get(i..i + 2)
+                    .with_context(|| format!("Can't find a pair of digits at {i}"))?;
+                u8::from_str_radix(pair, 16).with_context(|| format!("Can't parse '{pair}'"))
+            })
+            .collect()
+    }
+
     /// Parse `$ν5` into `5`, and `ν23` into `23`, and `42` into `42`.
     ///
     /// # Errors
@@ -198,3 +232,42 @@ fn simple_command() {
     assert_eq!("привет", g.data(1).unwrap().to_utf8().unwrap());
     assert_eq!(1, g.kid(0, Label::from_str("foo").unwrap()).unwrap());
 }
+
+#[test]
+fn binds_vertex_like_label() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    let mut s = Script::from_str("ADD(0); ADD(ν1); ADD($ν2); BIND(ν0, 1, ν2); BIND(0, $ν2, 42);");
+    s.deploy_to(&mut g).unwrap();
+    let a = Label::from_str("ν2").unwrap();
+    assert!(matches!(a, Label::Str(_)));
+    assert_eq!(1, g.kid(0, a).unwrap());
+    assert_eq!(2, g.kid(0, Label::from_str("42").unwrap()).unwrap());
+}
+
+#[test]
+fn adds_vertex_with_data() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    let mut s = Script::from_str("ADD(0, 2A); ADD(1); BIND(0, 1, foo);");
+    assert_eq!(3, s.deploy_to(&mut g).unwrap());
+    assert_eq!(vec![0x2A], g.data(0).unwrap().to_vec());
+    assert!(g.data(1).is_none());
+    assert!(Script::from_str("ADD(2, XYZ);").deploy_to(&mut g).is_err());
+}
+
+#[test]
+fn binds_quoted_label() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    let mut s = Script::from_str("ADD(0); ADD(1); BIND(0, 1, \"a b\");");
+    s.deploy_to(&mut g).unwrap();
+    assert_eq!(1, g.kid(0, Label::from_str("\"a b\"").unwrap()).unwrap());
+    assert!(g.kid(0, Label::from_str("ab").unwrap()).is_none());
+}
+
+#[test]
+fn refuses_broken_bytes() {
+    assert_eq!(vec![0xDE, 0xAD], Script::parse_bytes("DEAD").unwrap());
+    assert!(Script::parse_bytes("DEZZ").is_err());
+    assert!(Script::parse_bytes("DEA").is_err());
+    assert!(Script::parse_bytes("Dé").is_err());
+    assert!(Script::parse_data("DE-ZZ").is_err());
+}
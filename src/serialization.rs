@@ -18,10 +18,13 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::Sodg;
+use crate::{Label, Sodg, Vertex};
 use anyhow::{Context, Result};
-use bincode::{deserialize, serialize};
+use bincode::{deserialize, deserialize_from, serialize};
 use log::trace;
+use serde::de::{Error, MapAccess, Visitor};
+use serde::Deserializer;
+use std::fmt::Formatter;
 use std::fs;
 use std::path::Path;
 use std::time::Instant;
@@ -33,14 +36,34 @@ impl<const N: usize> Sodg<N> {
     /// The function returns the size of the file just saved. In order
     /// to restore from the file, use [`Sodg::load`].
     ///
+    /// The table of symbols of [`Sodg::intern`] goes after the graph,
+    /// thus the files saved before the table was introduced
+    /// are still loaded, just without it.
+    ///
+    /// The file is written atomically: the bytes go to a temporary file
+    /// next to it, which is then renamed to `path`. Thus, if the process
+    /// crashes in the middle, the file either stays as it was before
+    /// or is entirely new, but never half-written.
+    ///
     /// # Errors
     ///
     /// If impossible to save, an error will be returned.
     pub fn save(&self, path: &Path) -> Result<usize> {
         let start = Instant::now();
-        let bytes: Vec<u8> = serialize(self).with_context(|| "Failed to serialize")?;
+        let mut bytes: Vec<u8> = serialize(self).with_context(|| "Failed to serialize")?;
+        bytes.extend(serialize(&self.symbols).with_context(|| "Failed to serialize symbols")?);
         let size = bytes.len();
-        fs::write(path, bytes).with_context(|| format!("Can't write to {}", path.display()))?;
+        let name = path
+            .file_name()
+            .with_context(|| format!("Can't write to {}, it is not a file", path.display()))?;
+        let mut tmp = name.to_os_string();
+        tmp.push(".tmp");
+        let tmp = path.with_file_name(tmp);
+        fs::write(&tmp, bytes).with_context(|| format!("Can't write to {}", tmp.display()))?;
+        if let Err(e) = fs::rename(&tmp, path) {
+            let _ = fs::remove_file(&tmp);
+            return Err(e).with_context(|| format!("Can't move to {}", path.display()));
+        }
         trace!(
             "Serialized {} vertices ({} bytes) to {} in {:?}",
             self.len(),
@@ -56,13 +79,14 @@ impl<const N: usize> Sodg<N> {
     ///
     /// # Errors
     ///
-    /// If impossible to load, an error will be returned.
+    /// If impossible to load, an error will be returned. For example, if
+    /// a vertex in the file has more than `N` edges.
     pub fn load(path: &Path) -> Result<Self> {
         let start = Instant::now();
         let bytes =
             fs::read(path).with_context(|| format!("Can't read from {}", path.display()))?;
         let size = bytes.len();
-        let sodg: Self = deserialize(&bytes)
+        let sodg = Self::decode(&bytes)
             .with_context(|| format!("Can't deserialize from {}", path.display()))?;
         trace!(
             "Deserialized {} vertices ({} bytes) from {} in {:?}",
@@ -73,6 +97,123 @@ impl<const N: usize> Sodg<N> {
         );
         Ok(sodg)
     }
+
+    /// Load the entire [`Sodg`] from a binary file previously
+    /// created by [`Sodg::save`], mapping the file into memory instead
+    /// of reading it.
+    ///
+    /// This is what [`Sodg::load`] does, but the content of the file
+    /// is not copied to the heap, which matters when the file is large.
+    /// The function is available only with the `mmap` feature.
+    ///
+    /// # Errors
+    ///
+    /// If impossible to load, an error will be returned. For example, if
+    /// a vertex in the file has more than `N` edges.
+    #[cfg(feature = "mmap")]
+    pub fn load_mmap(path: &Path) -> Result<Self> {
+        let start = Instant::now();
+        let map =
+            mmap(path).with_context(|| format!("Can't map {} into memory", path.display()))?;
+        let sodg = Self::decode(&map[..])
+            .with_context(|| format!("Can't deserialize from {}", path.display()))?;
+        trace!(
+            "Deserialized {} vertices ({} bytes) from mapped {} in {:?}",
+            sodg.len(),
+            map.len(),
+            path.display(),
+            start.elapsed()
+        );
+        Ok(sodg)
+    }
+
+    /// Make a [`Sodg`] from the bytes made by [`Sodg::save`], with
+    /// the table of symbols after the graph, if it is there.
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut rest = bytes;
+        let mut sodg: Self = deserialize_from(&mut rest)?;
+        if !rest.is_empty() {
+            sodg.symbols = deserialize(rest).context("Can't deserialize symbols")?;
+        }
+        sodg.alive = sodg.keys().len();
+        Ok(sodg)
+    }
+}
+
+/// Map the file into memory, for reading only.
+#[cfg(feature = "mmap")]
+fn mmap(path: &Path) -> std::io::Result<memmap2::Mmap> {
+    let file = fs::File::open(path)?;
+    // SAFETY: the map is only read; modifying the file by another
+    // process while it is mapped is a misuse of Sodg::load_mmap().
+    unsafe { memmap2::Mmap::map(&file) }
+}
+
+/// Deserialize the vertices of a [`Sodg`], mentioning the ID of
+/// the vertex that failed to deserialize.
+pub fn vertices<'de, D, const N: usize>(d: D) -> Result<emap::Map<Vertex<N>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct Vi<const N: usize>;
+    impl<'de, const N: usize> Visitor<'de> for Vi<N> {
+        type Value = emap::Map<Vertex<N>>;
+
+        fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+            f.write_str("a map of vertices")
+        }
+
+        fn visit_map<M: MapAccess<'de>>(self, mut access: M) -> Result<Self::Value, M::Error> {
+            let mut all = vec![];
+            while let Some(v) = access.next_key::<usize>()? {
+                let vtx = access
+                    .next_value::<Vertex<N>>()
+                    .map_err(|e| M::Error::custom(format!("Can't deserialize ν{v}: {e}")))?;
+                all.push((v, vtx));
+            }
+            let cap = all.iter().map(|(v, _)| *v + 1).max().unwrap_or(0);
+            let mut map = emap::Map::with_capacity_none(cap);
+            for (v, vtx) in all {
+                map.insert(v, vtx);
+            }
+            Ok(map)
+        }
+    }
+    d.deserialize_map(Vi::<N>)
+}
+
+/// Deserialize the edges of a vertex, making sure there
+/// are no more than `N` of them.
+pub fn edges<'de, D, const N: usize>(d: D) -> Result<micromap::Map<Label, usize, N>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct Vi<const N: usize>;
+    impl<'de, const N: usize> Visitor<'de> for Vi<N> {
+        type Value = micromap::Map<Label, usize, N>;
+
+        fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+            f.write_str("a map of edges")
+        }
+
+        fn visit_map<M: MapAccess<'de>>(self, mut access: M) -> Result<Self::Value, M::Error> {
+            let mut map = micromap::Map::new();
+            let mut total = 0;
+            while let Some((a, to)) = access.next_entry::<Label, usize>()? {
+                if total < N {
+                    map.insert(a, to);
+                }
+                total += 1;
+            }
+            if total > N {
+                return Err(M::Error::custom(format!(
+                    "the vertex has {total} edges, but this Sodg allows only {N}"
+                )));
+            }
+            Ok(map)
+        }
+    }
+    d.deserialize_map(Vi::<N>)
 }
 
 #[cfg(test)]
@@ -81,9 +222,6 @@ use tempfile::TempDir;
 #[cfg(test)]
 use crate::Hex;
 
-#[cfg(test)]
-use crate::Label;
-
 #[cfg(test)]
 use std::str::FromStr;
 
@@ -99,6 +237,23 @@ fn can_save() {
     assert!(file.metadata().unwrap().len() > 0);
 }
 
+#[test]
+fn saves_atomically() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    let tmp = TempDir::new().unwrap();
+    let file = tmp.path().join("foo.sodg");
+    fs::write(&file, "garbage").unwrap();
+    let size = g.save(file.as_path()).unwrap();
+    assert_eq!(size as u64, file.metadata().unwrap().len());
+    let files: Vec<String> = fs::read_dir(tmp.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(vec!["foo.sodg".to_string()], files);
+    assert_eq!(1, Sodg::<16>::load(file.as_path()).unwrap().len());
+}
+
 #[test]
 fn saves_and_loads() {
     let mut g: Sodg<1> = Sodg::empty(100);
@@ -110,3 +265,49 @@ fn saves_and_loads() {
     let after: Sodg<1> = Sodg::load(file.as_path()).unwrap();
     assert_eq!(g.inspect(0).unwrap(), after.inspect(0).unwrap());
 }
+
+#[test]
+fn refuses_to_load_too_many_edges() {
+    let mut g: Sodg<4> = Sodg::empty(16);
+    g.add(0);
+    g.add(1);
+    g.add(5);
+    g.bind(5, 0, Label::Alpha(0));
+    g.bind(5, 1, Label::Alpha(1));
+    g.bind(5, 1, Label::Alpha(2));
+    let tmp = TempDir::new().unwrap();
+    let file = tmp.path().join("foo.sodg");
+    g.save(file.as_path()).unwrap();
+    assert_eq!(3, Sodg::<4>::load(file.as_path()).unwrap().kids(5).count());
+    let msg = format!("{:#}", Sodg::<2>::load(file.as_path()).err().unwrap());
+    assert!(
+        msg.contains("ν5: the vertex has 3 edges, but this Sodg allows only 2"),
+        "{msg}"
+    );
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn loads_through_memory_map() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("foo").unwrap());
+    g.put(1, &Hex::from_str_bytes("hello"));
+    let tmp = TempDir::new().unwrap();
+    let file = tmp.path().join("foo.sodg");
+    g.save(file.as_path()).unwrap();
+    let loaded: Sodg<16> = Sodg::load(file.as_path()).unwrap();
+    let mapped: Sodg<16> = Sodg::load_mmap(file.as_path()).unwrap();
+    assert_eq!(loaded.to_xml_full().unwrap(), mapped.to_xml_full().unwrap());
+}
+
+#[test]
+fn loads_file_without_symbols() {
+    let file = Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/baseline.sodg");
+    let mut g: Sodg<16> = Sodg::load(file.as_path()).unwrap();
+    assert_eq!(2, g.len());
+    let v = g.kid(0, Label::from_str("foo").unwrap()).unwrap();
+    assert_eq!(42, g.data(v).unwrap().to_i64().unwrap());
+    assert!(g.label_name(Label::Id(0)).is_err());
+}
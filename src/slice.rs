@@ -18,15 +18,33 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::{Label, Sodg};
+use crate::{Label, Persistence, Sodg};
 use anyhow::Result;
 use log::trace;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 impl<const N: usize> Sodg<N> {
     /// Take a slice of the graph, keeping only the vertex specified
     /// by the locator and its kids, recursively found in the entire graph.
     ///
+    /// The data of the vertices is copied to the slice too, together with
+    /// its persistence state: the data taken in this graph stays taken
+    /// in the slice.
+    ///
+    /// The vertices keep their IDs in the slice, thus references to them
+    /// made outside of the graph remain valid. The capacity of the slice
+    /// is the same as the capacity of the graph. For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(7);
+    /// g.bind(0, 7, Label::Alpha(0));
+    /// let slice = g.slice(7).unwrap();
+    /// assert_eq!(vec![7], slice.keys());
+    /// ```
+    ///
     /// # Errors
     ///
     /// If impossible to slice, an error will be returned.
@@ -46,6 +64,9 @@ impl<const N: usize> Sodg<N> {
     /// but only if the provided predicate agrees with the selection of
     /// the kids.
     ///
+    /// Just like in [`Sodg::slice`], the vertices keep their IDs. Edges
+    /// leading to the vertices that are not in the slice are dropped.
+    ///
     /// # Errors
     ///
     /// There could be errors too.
@@ -76,27 +97,100 @@ impl<const N: usize> Sodg<N> {
                 }
             }
         }
-        let mut ng = Self::empty(self.vertices.capacity());
-        for (v1, vtx) in self.vertices.iter().filter(|(v, _)| done.contains(v)) {
-            if done.contains(&v1) {
-                ng.add(v1);
+        let ng = self.sliced(&done);
+        trace!(
+            "#slice_some: taken {} vertices out of {} at ν{v}",
+            ng.len(),
+            self.len()
+        );
+        Ok(ng)
+    }
+
+    /// Take a slice of the graph, keeping only the vertex and all vertices
+    /// it can be reached from, following the edges backwards.
+    ///
+    /// For example, here the sibling `ν3` is not in the slice:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(0, 1, Label::Alpha(0));
+    /// g.add(2);
+    /// g.bind(1, 2, Label::Alpha(0));
+    /// g.add(3);
+    /// g.bind(0, 3, Label::Alpha(1));
+    /// let mut keys = g.slice_up(2).unwrap().keys();
+    /// keys.sort_unstable();
+    /// assert_eq!(vec![0, 1, 2], keys);
+    /// ```
+    ///
+    /// Just like in [`Sodg::slice`], the vertices keep their IDs and
+    /// their data. Edges leading to the vertices that are not in the slice
+    /// are dropped.
+    ///
+    /// # Errors
+    ///
+    /// If impossible to slice, an error will be returned.
+    ///
+    /// # Panics
+    ///
+    /// If vertex `v` is absent, it will panic.
+    pub fn slice_up(&self, v: usize) -> Result<Self> {
+        let mut parents: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (v1, vtx) in self.vertices.iter() {
+            for v2 in vtx.edges.values() {
+                parents.entry(*v2).or_default().push(v1);
             }
-            for (k, v2) in &vtx.edges {
-                if done.contains(v2) {
-                    ng.add(*v2);
-                    ng.bind(v1, *v2, *k);
+        }
+        let mut done = HashSet::new();
+        let mut todo = vec![v];
+        done.insert(v);
+        while let Some(v2) = todo.pop() {
+            for v1 in parents.get(&v2).into_iter().flatten() {
+                if done.insert(*v1) {
+                    todo.push(*v1);
                 }
             }
         }
+        let ng = self.sliced(&done);
         trace!(
-            "#slice_some: taken {} vertices out of {} at ν{v}",
+            "#slice_up: taken {} vertices out of {} at ν{v}",
             ng.len(),
             self.len()
         );
         Ok(ng)
     }
+
+    /// Make a new graph of the given vertices of this one, with their data
+    /// and the edges between them.
+    fn sliced(&self, done: &HashSet<usize>) -> Self {
+        let mut ng = Self::empty(self.vertices.capacity());
+        for v1 in done {
+            ng.add(*v1);
+        }
+        for (v1, vtx) in self.vertices.iter().filter(|(v, _)| done.contains(v)) {
+            for (k, v2) in &vtx.edges {
+                if done.contains(v2) {
+                    ng.bind(v1, *v2, *k);
+                }
+            }
+        }
+        for (v1, vtx) in self.vertices.iter().filter(|(v, _)| done.contains(v)) {
+            match vtx.persistence {
+                Persistence::Stored => ng.put(v1, &vtx.data),
+                Persistence::Taken => ng.put_taken(v1, &vtx.data),
+                Persistence::Empty => {}
+            }
+        }
+        ng
+    }
 }
 
+#[cfg(test)]
+use crate::Hex;
+
 #[cfg(test)]
 use std::str::FromStr;
 
@@ -138,3 +232,73 @@ fn skips_some_vertices() {
     assert_eq!(2, slice.len());
     assert_eq!(1, slice.kids(0).count());
 }
+
+#[test]
+fn slices_with_data() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("foo").unwrap());
+    g.add(2);
+    g.bind(1, 2, Label::from_str("bar").unwrap());
+    g.put(1, &Hex::from(42));
+    g.add(3);
+    g.bind(1, 3, Label::from_str("xyz").unwrap());
+    g.put(3, &Hex::from(7));
+    g.data(3);
+    let mut slice = g.slice(1).unwrap();
+    assert_eq!(3, slice.len());
+    assert_eq!(Hex::from(7), slice.peek(3).unwrap());
+    assert_eq!(42, slice.data(1).unwrap().to_i64().unwrap());
+    assert_eq!(7, slice.data(3).unwrap().to_i64().unwrap());
+    assert!(slice.data(2).is_none());
+}
+
+#[test]
+fn preserves_ids_in_slice() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(10);
+    g.bind(0, 10, Label::from_str("foo").unwrap());
+    g.add(20);
+    g.bind(10, 20, Label::from_str("bar").unwrap());
+    g.add(30);
+    g.bind(20, 30, Label::from_str("+out").unwrap());
+    g.bind(20, 0, Label::from_str("up").unwrap());
+    let slice = g
+        .slice_some(10, |_, _, a| !a.to_string().starts_with('+'))
+        .unwrap();
+    assert_eq!(vec![0, 10, 20], slice.keys());
+    for v in slice.keys() {
+        for (a, to) in slice.kids(v) {
+            assert_eq!(Some(*to), g.kid(v, *a));
+        }
+    }
+    assert!(slice.kid_str(20, "+out").is_none());
+    let narrow = g.slice_some(10, |_, to, _| to != 30 && to != 0).unwrap();
+    assert_eq!(vec![10, 20], narrow.keys());
+    assert_eq!(0, narrow.kids(20).count());
+}
+
+#[test]
+fn slices_upwards() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("a").unwrap());
+    g.add(2);
+    g.bind(1, 2, Label::from_str("b").unwrap());
+    g.put(2, &Hex::from(42));
+    g.add(3);
+    g.bind(0, 3, Label::from_str("c").unwrap());
+    g.add(4);
+    g.bind(1, 4, Label::from_str("d").unwrap());
+    g.bind(2, 1, Label::Greek('ρ'));
+    let mut slice = g.slice_up(2).unwrap();
+    let mut keys = slice.keys();
+    keys.sort_unstable();
+    assert_eq!(vec![0, 1, 2], keys);
+    assert_eq!(vec![1], slice.kids(0).map(|(_, v)| *v).collect::<Vec<_>>());
+    assert_eq!(Some(1), slice.kid(2, Label::Greek('ρ')));
+    assert_eq!(42, slice.data(2).unwrap().to_i64().unwrap());
+}
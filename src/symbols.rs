@@ -0,0 +1,116 @@
+// Copyright (c) 2022-2025 Objectionary.com
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included
+// in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{Label, Sodg};
+use anyhow::{anyhow, Result};
+
+impl<const N: usize> Sodg<N> {
+    /// Register a name in the symbol table of the graph and
+    /// return a [`Label::Id`] pointing to it.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// let a = g.intern("a-very-long-attribute-name");
+    /// g.bind(0, 1, a);
+    /// assert_eq!(a, g.intern("a-very-long-attribute-name"));
+    /// assert_eq!("a-very-long-attribute-name", g.label_name(a).unwrap());
+    /// ```
+    ///
+    /// The same name always gets the same ID. The table is saved and loaded
+    /// together with the graph, but the IDs are local to it: [`Sodg::merge`]
+    /// doesn't translate them between graphs.
+    ///
+    /// # Panics
+    ///
+    /// If there are more than `u32::MAX` names in the table, it will panic.
+    pub fn intern(&mut self, name: &str) -> Label {
+        Label::Id(self.symbols.intern(name))
+    }
+
+    /// Find a [`Label::Id`] of a name already registered
+    /// by [`Sodg::intern`].
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Sodg;
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// assert!(g.symbol("foo").is_none());
+    /// let a = g.intern("foo");
+    /// assert_eq!(Some(a), g.symbol("foo"));
+    /// ```
+    #[must_use]
+    pub fn symbol(&self, name: &str) -> Option<Label> {
+        self.symbols.find(name).map(Label::Id)
+    }
+
+    /// Print a label, taking the name of a [`Label::Id`] from
+    /// the symbol table of the graph.
+    ///
+    /// Labels of other kinds are printed as usual.
+    ///
+    /// # Errors
+    ///
+    /// If the ID is not in the table, an error will be returned.
+    pub fn label_name(&self, a: Label) -> Result<String> {
+        match a {
+            Label::Id(i) => self.symbols.resolve(i).ok_or_else(|| {
+                anyhow!(
+                    "There is no symbol {a} in the table of {}",
+                    self.symbols.len()
+                )
+            }),
+            _ => Ok(a.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+use tempfile::TempDir;
+
+#[test]
+fn interns_long_names() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.add(2);
+    let first = g.intern("the-first-long-name");
+    let second = g.intern("the-second-long-name");
+    assert_ne!(first, second);
+    g.bind(0, 1, first);
+    g.bind(0, 2, second);
+    assert_eq!(second, g.intern("the-second-long-name"));
+    assert_eq!(Some(2), g.kid(0, second));
+    assert_eq!("the-first-long-name", g.label_name(first).unwrap());
+    assert_eq!("α1", g.label_name(Label::Alpha(1)).unwrap());
+    assert!(g.label_name(Label::Id(42)).is_err());
+    let tmp = TempDir::new().unwrap();
+    let file = tmp.path().join("foo.sodg");
+    g.save(file.as_path()).unwrap();
+    let after: Sodg<16> = Sodg::load(file.as_path()).unwrap();
+    let a = after.symbol("the-second-long-name").unwrap();
+    assert_eq!(Some(2), after.kid(0, a));
+    assert_eq!("the-first-long-name", after.label_name(first).unwrap());
+}
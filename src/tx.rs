@@ -0,0 +1,100 @@
+// Copyright (c) 2022-2025 Objectionary.com
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included
+// in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::Sodg;
+use anyhow::Result;
+#[cfg(debug_assertions)]
+use log::trace;
+
+impl<const N: usize> Sodg<N> {
+    /// Run a group of changes with all-or-nothing semantics.
+    ///
+    /// If `f` returns an error, the graph is restored to the state it had
+    /// before the transaction, and the error is returned. For example:
+    ///
+    /// ```
+    /// use anyhow::anyhow;
+    /// use sodg::Sodg;
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// let r = g.transaction(|g| {
+    ///     g.add(1);
+    ///     Err(anyhow!("oops"))
+    /// });
+    /// assert!(r.is_err());
+    /// assert_eq!(1, g.len());
+    /// ```
+    ///
+    /// The snapshot is a full clone of the graph, so a transaction costs
+    /// as much memory as the graph itself.
+    ///
+    /// # Errors
+    ///
+    /// If `f` fails, its error is returned.
+    pub fn transaction(&mut self, f: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        let before = self.clone();
+        let ret = f(self);
+        if ret.is_err() {
+            *self = before;
+            #[cfg(debug_assertions)]
+            trace!("#transaction: rolled back to {} vertices", self.len());
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+use crate::{Hex, Label};
+
+#[cfg(test)]
+use anyhow::anyhow;
+
+#[test]
+fn rolls_back_failed_transaction() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::Alpha(0));
+    let before = g.to_xml_full().unwrap();
+    let r = g.transaction(|g| {
+        g.add(2);
+        g.add(3);
+        g.bind(1, 2, Label::Alpha(0));
+        g.bind(2, 3, Label::Alpha(0));
+        g.put(3, &Hex::from(42));
+        Err(anyhow!("failed on purpose"))
+    });
+    assert_eq!("failed on purpose", r.err().unwrap().to_string());
+    assert_eq!(2, g.len());
+    assert_eq!(before, g.to_xml_full().unwrap());
+}
+
+#[test]
+fn commits_successful_transaction() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.transaction(|g| {
+        g.add(1);
+        g.bind(0, 1, Label::Alpha(0));
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(1, g.kid(0, Label::Alpha(0)).unwrap());
+}
@@ -18,11 +18,32 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::{Persistence, Sodg};
+use crate::{Persistence, Sodg, Xml};
 use anyhow::Result;
 use itertools::Itertools;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::Write;
 use xml_builder::{XMLBuilder, XMLElement, XMLVersion};
 
+/// A minimal DTD of the XML documents made by [`Sodg::to_xml`]
+/// and [`Sodg::to_xml_full`].
+///
+/// It is referenced as `sodg.dtd` by [`Sodg::to_xml_validated`].
+pub const SODG_DTD: &str = "<!ELEMENT sodg (v*)>
+<!ELEMENT v (e*, data?)>
+<!ATTLIST v
+  id CDATA #REQUIRED
+  branch CDATA #IMPLIED
+  state (empty|stored|taken) #IMPLIED>
+<!ELEMENT e EMPTY>
+<!ATTLIST e
+  a CDATA #REQUIRED
+  to CDATA #REQUIRED>
+<!ELEMENT data (#PCDATA)>
+";
+
 impl<const N: usize> Sodg<N> {
     /// Make XML graph.
     ///
@@ -61,6 +82,156 @@ impl<const N: usize> Sodg<N> {
     /// If it's impossible to print it to XML, an [`Err`] may be returned. Problems may also
     /// be caused by XML errors from the XML builder library.
     pub fn to_xml(&self) -> Result<String> {
+        let mut writer: Vec<u8> = Vec::new();
+        self.write_xml(&mut writer)?;
+        Ok(std::str::from_utf8(&writer)?.to_string())
+    }
+
+    /// Make XML graph, just like [`Sodg::to_xml`] does, but with
+    /// a `DOCTYPE` declaration referring to `sodg.dtd`.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Sodg, SODG_DTD};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// let xml = g.to_xml_validated().unwrap();
+    /// assert!(xml.contains("<!DOCTYPE sodg SYSTEM \"sodg.dtd\">"));
+    /// ```
+    ///
+    /// The DTD itself is available as [`SODG_DTD`], to be saved
+    /// next to the XML document.
+    ///
+    /// # Errors
+    ///
+    /// If it's impossible to print it to XML, an [`Err`] may be returned.
+    pub fn to_xml_validated(&self) -> Result<String> {
+        let xml = self.to_xml()?;
+        let (head, body) = xml.split_once('\n').unwrap_or(("", xml.as_str()));
+        Ok(format!(
+            "{head}\n<!DOCTYPE sodg SYSTEM \"sodg.dtd\">\n{body}"
+        ))
+    }
+
+    /// Print XML graph to the writer provided.
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::{Hex, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.put(0, &Hex::from_str_bytes("hello"));
+    /// let mut out = std::io::stdout();
+    /// g.write_xml(&mut out).unwrap();
+    /// ```
+    ///
+    /// The output is exactly the same as the one made by [`Sodg::to_xml`],
+    /// but the document is not returned as a [`String`], which helps
+    /// when the graph is large and the XML goes to a file.
+    ///
+    /// # Errors
+    ///
+    /// If it's impossible to print it to XML or to write it to the writer,
+    /// an [`Err`] may be returned.
+    pub fn write_xml<W: Write>(&self, w: &mut W) -> Result<()> {
+        self.print_xml(w, false, |v| v)
+    }
+
+    /// Make a printable XML document, without rendering it into a [`String`].
+    ///
+    /// For example:
+    ///
+    /// ```
+    /// use sodg::Sodg;
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// println!("{}", g.xml());
+    /// assert_eq!(g.to_xml().unwrap(), g.xml().to_string());
+    /// ```
+    ///
+    /// If it's impossible to print the graph to XML, the formatter
+    /// gets an error.
+    #[must_use]
+    pub const fn xml(&self) -> Xml<'_, N> {
+        Xml(self)
+    }
+
+    /// Make XML graph, including the branch and the persistence
+    /// state of each vertex.
+    ///
+    /// For example, for this code:
+    ///
+    /// ```
+    /// use sodg::Hex;
+    /// use sodg::Sodg;
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.put(0, &Hex::from_str_bytes("hello"));
+    /// let xml = g.to_xml_full().unwrap();
+    /// println!("{}", xml);
+    /// ```
+    ///
+    /// The printout will look like this:
+    ///
+    /// ```xml
+    /// <?xml version="1.1" encoding="UTF-8"?>
+    /// <sodg>
+    ///     <v id="0" branch="1" state="stored">
+    ///         <data>68 65 6C 6C 6F</data>
+    ///     </v>
+    /// </sodg>
+    /// ```
+    ///
+    /// The `state` attribute is either `empty`, `stored`, or `taken`. Use
+    /// [`Sodg::to_xml`] if you need a shorter and more readable output.
+    ///
+    /// # Errors
+    ///
+    /// If it's impossible to print it to XML, an [`Err`] may be returned. Problems may also
+    /// be caused by XML errors from the XML builder library.
+    pub fn to_xml_full(&self) -> Result<String> {
+        let mut writer: Vec<u8> = Vec::new();
+        self.print_xml(&mut writer, true, |v| v)?;
+        Ok(std::str::from_utf8(&writer)?.to_string())
+    }
+
+    /// Make XML graph, just like [`Sodg::to_xml`] does, but with vertices
+    /// ordered by the key provided, instead of their IDs.
+    ///
+    /// For example, this puts vertices with more edges first:
+    ///
+    /// ```
+    /// use std::cmp::Reverse;
+    /// use sodg::{Label, Sodg};
+    /// let mut g : Sodg<16> = Sodg::empty(256);
+    /// g.add(0);
+    /// g.add(1);
+    /// g.bind(1, 0, Label::Alpha(0));
+    /// let xml = g.to_xml_ordered(|v| Reverse(g.kids(v).count())).unwrap();
+    /// assert!(xml.find("<v id=\"1\"").unwrap() < xml.find("<v id=\"0\"").unwrap());
+    /// ```
+    ///
+    /// Vertices with equal keys are ordered by their IDs.
+    ///
+    /// # Errors
+    ///
+    /// If it's impossible to print it to XML, an [`Err`] may be returned.
+    pub fn to_xml_ordered<K: Ord>(&self, key: impl Fn(usize) -> K) -> Result<String> {
+        let mut writer: Vec<u8> = Vec::new();
+        self.print_xml(&mut writer, false, key)?;
+        Ok(std::str::from_utf8(&writer)?.to_string())
+    }
+
+    /// Print XML graph to the writer, with or without branches
+    /// and persistence states, ordering vertices by the key.
+    fn print_xml<W: Write, K: Ord>(
+        &self,
+        w: &mut W,
+        full: bool,
+        key: impl Fn(usize) -> K,
+    ) -> Result<()> {
         let mut xml = XMLBuilder::new()
             .version(XMLVersion::XML1_1)
             .encoding("UTF-8".into())
@@ -69,10 +240,21 @@ impl<const N: usize> Sodg<N> {
         for (v, vtx) in self
             .vertices
             .iter()
-            .sorted_by_key(|(v, _)| <usize>::clone(v))
+            .sorted_by(|(a, _), (b, _)| key(*a).cmp(&key(*b)).then(a.cmp(b)))
         {
             let mut v_node = XMLElement::new("v");
             v_node.add_attribute("id", v.to_string().as_str());
+            if full {
+                v_node.add_attribute("branch", vtx.branch.to_string().as_str());
+                v_node.add_attribute(
+                    "state",
+                    match vtx.persistence {
+                        Persistence::Empty => "empty",
+                        Persistence::Stored => "stored",
+                        Persistence::Taken => "taken",
+                    },
+                );
+            }
             for e in vtx.edges.iter().sorted_by_key(|e| e.0) {
                 let mut e_node = XMLElement::new("e");
                 e_node.add_attribute("a", e.0.to_string().as_str());
@@ -87,9 +269,32 @@ impl<const N: usize> Sodg<N> {
             root.add_child(v_node)?;
         }
         xml.set_root_element(root);
-        let mut writer: Vec<u8> = Vec::new();
-        xml.generate(&mut writer)?;
-        Ok(std::str::from_utf8(&writer)?.to_string())
+        xml.generate(w)?;
+        Ok(())
+    }
+}
+
+impl<const N: usize> Display for Xml<'_, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0
+            .print_xml(&mut FormatterWriter(f), false, |v| v)
+            .map_err(|_| fmt::Error)
+    }
+}
+
+/// A [`Formatter`] that accepts bytes, which must be valid UTF-8.
+struct FormatterWriter<'a, 'b>(&'a mut Formatter<'b>);
+
+impl Write for FormatterWriter<'_, '_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let s =
+            std::str::from_utf8(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.0.write_str(s).map_err(io::Error::other)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
     }
 }
 
@@ -128,3 +333,99 @@ fn prints_simple_graph() {
             .string()
     );
 }
+
+#[test]
+fn prints_state_of_taken_vertex() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("foo").unwrap());
+    g.put(1, &Hex::from(42));
+    g.data(1);
+    let xml = g.to_xml_full().unwrap();
+    let parser = sxd_document::parser::parse(xml.as_str()).unwrap();
+    let doc = parser.as_document();
+    assert_eq!(
+        "taken",
+        evaluate_xpath(&doc, "/sodg/v[@id=1]/@state")
+            .unwrap()
+            .string()
+    );
+    assert_eq!(
+        "empty",
+        evaluate_xpath(&doc, "/sodg/v[@id=0]/@state")
+            .unwrap()
+            .string()
+    );
+    assert!(!g.to_xml().unwrap().contains("state="));
+}
+
+#[test]
+fn writes_xml_to_vector() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("foo").unwrap());
+    g.put(1, &Hex::from_str_bytes("hi"));
+    let mut out: Vec<u8> = Vec::new();
+    g.write_xml(&mut out).unwrap();
+    let xml = String::from_utf8(out).unwrap();
+    assert_eq!(g.to_xml().unwrap(), xml);
+    let parser = sxd_document::parser::parse(xml.as_str()).unwrap();
+    let doc = parser.as_document();
+    assert_eq!(
+        "68 69",
+        evaluate_xpath(&doc, "/sodg/v[@id=1]/data")
+            .unwrap()
+            .string()
+    );
+}
+
+#[test]
+fn writes_xml_through_wrapper() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.put(0, &Hex::from_str_bytes("привет"));
+    g.add(1);
+    g.bind(0, 1, Label::from_str("foo").unwrap());
+    assert_eq!(g.to_xml().unwrap(), format!("{}", g.xml()));
+}
+
+#[test]
+fn orders_vertices_in_xml() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    for v in 0..3 {
+        g.add(v);
+    }
+    g.bind(1, 0, Label::Alpha(0));
+    g.bind(1, 2, Label::Alpha(1));
+    g.bind(2, 0, Label::Alpha(0));
+    let xml = g
+        .to_xml_ordered(|v| std::cmp::Reverse(g.kids(v).count()))
+        .unwrap();
+    let parser = sxd_document::parser::parse(xml.as_str()).unwrap();
+    let doc = parser.as_document();
+    assert_eq!(
+        "1",
+        evaluate_xpath(&doc, "/sodg/v[1]/@id").unwrap().string()
+    );
+    assert_eq!(
+        "2",
+        evaluate_xpath(&doc, "/sodg/v[2]/@id").unwrap().string()
+    );
+    assert_eq!(g.to_xml().unwrap(), g.to_xml_ordered(|v| v).unwrap());
+}
+
+#[test]
+fn prints_xml_with_doctype() {
+    let mut g: Sodg<16> = Sodg::empty(256);
+    g.add(0);
+    g.add(1);
+    g.bind(0, 1, Label::from_str("foo").unwrap());
+    let xml = g.to_xml_validated().unwrap();
+    assert!(xml.starts_with("<?xml "));
+    assert!(xml.contains("<!DOCTYPE sodg SYSTEM \"sodg.dtd\">"));
+    assert!(!g.to_xml().unwrap().contains("DOCTYPE"));
+    assert!(!SODG_DTD.is_empty());
+    assert!(SODG_DTD.contains("<!ELEMENT sodg"));
+}